@@ -5,8 +5,6 @@ use vaabbit::{Actor, ID, TypedID, World, physics::PhysicsClass, shapes::{AABB, C
 use vibbit::{Color, Vibbit};
 use macroquad::{color, prelude::rand};
 
-static mut DT : f32 = 0.0;
-
 struct Rect {
     vel: glam::Vec2,
 }
@@ -26,7 +24,8 @@ impl Actor<()> for Rect {
     }
 
     fn update(&mut self, _id: &ID<Self>, _world: &mut World, ctx: &mut ()) {
-        let pos = self.move_by(&(self.vel * unsafe{DT} * 60.0), _world);
+        // runs at a fixed 60 ticks per second, so velocity is in pixels per tick
+        let pos = self.move_by(&self.vel, _world);
         
         if pos.x < 0.0 || pos.x > 1280.0 - 32.0 {
             self.vel.x *= -1.0;
@@ -57,14 +56,13 @@ fn main() {
     let font = vib.default_font();
 
     while !vib.should_close() {
-        world.update_systems(&mut ());
-
-        unsafe {DT = vib.get_delta_time(); }
+        world.step(vib.get_delta_time(), &mut ());
+        let alpha = world.alpha();
 
         vib.clear_screen(Color::new(64,64,64,255));
 
         for (id, rect) in world.query::<Rect>() {
-            let pos = world.get_pos(id).clone();
+            let Some(pos) = world.interpolated_pos(id, alpha) else { continue };
             let mut color = Color::new(255,255,255,255);
             let collided = world.get_colliding_bodies(&id).len();
 
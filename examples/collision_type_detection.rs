@@ -87,11 +87,11 @@ impl Actor<()> for Player {
                 let random_pos = vaabbit::Vec2::new(rand::gen_range(0.0, 640.0), rand::gen_range(0.0, 480.0));
                 let c_id = world.add_actor(Coin {eaten: false});
                 println!("updating new coin pos: {:?}", c_id);
-                
 
-                world.with_world(&c_id, move |_coin: &mut Coin, world| {
-                    world.set_pos(c_id, random_pos);
-                    println!("--x moving coin to {:?}", c_id);
+                // the body is there right away, and actions on the new coin run once it's stored
+                world.set_pos(c_id, random_pos);
+                world.with(&c_id, move |_coin: &mut Coin| {
+                    println!("--x moved coin to {:?}", c_id);
                 });
             });
         }
@@ -5,6 +5,7 @@ use macroquad::{prelude::rand};
 
 struct Player {
     vel: f32,
+    grounded: bool,
 }
 
 struct Ground {
@@ -25,16 +26,18 @@ impl Actor<Vibbit> for Block {
         )
     }
 
-    fn update(&mut self, id: &ID<Self>, world: &mut World, vib: &mut Vibbit) where Self: Sized {
-        let body = world.get_physics_body(&id).unwrap();
-        let color = vibbit::Color::new(255,255,255,255);
-        vib.draw_rect(body.pos(), body.bounds().width(), body.bounds().height(), color);
-
+    fn update(&mut self, id: &ID<Self>, world: &mut World, _vib: &mut Vibbit) where Self: Sized {
         if rand::gen_range(0.0, 1000.0) < 0.5 {
             world.remove_actor(id);
             world.add_actor(Block {});
         }
     }
+
+    fn draw(&self, id: &ID<Self>, world: &World, vib: &mut Vibbit) {
+        let body = world.get_physics_body(id).unwrap();
+        let color = vibbit::Color::new(255,255,255,255);
+        vib.draw_rect(body.pos(), body.bounds().width(), body.bounds().height(), color);
+    }
 }
 
 struct Coin {
@@ -51,9 +54,13 @@ impl Actor<Vibbit> for Coin {
         )
     }
 
-    fn update(&mut self, id: &ID<Self>, world: &mut World, vib: &mut Vibbit) where Self: Sized {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _vib: &mut Vibbit) where Self: Sized {
+
+    }
+
+    fn draw(&self, id: &ID<Self>, world: &World, vib: &mut Vibbit) {
         let color = vibbit::Color::new(255,255,128,255);
-        vib.draw_rect(self.pos(world), 8.0, 8.0, color);
+        vib.draw_rect(world.get_pos(id), 8.0, 8.0, color);
     }
 
     fn on_collision<'a>(&mut self, id: &ID<Self>, other: vaabbit::TypedID, world: &'a mut World) {
@@ -78,11 +85,20 @@ impl Actor<Vibbit> for Ground {
         body
     }
 
-    fn update(&mut self, id: &ID<Self>, world: &mut World, vib: &mut Vibbit) where Self: Sized {
-        let body = world.get_physics_body(&id).unwrap();
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _vib: &mut Vibbit) where Self: Sized {
+
+    }
+
+    fn draw(&self, id: &ID<Self>, world: &World, vib: &mut Vibbit) {
+        let body = world.get_physics_body(id).unwrap();
         let pos = body.pos() + Vec2::new(-320.0, 16.0);
         vib.draw_rect(pos, 640., 16., vibbit::Color::new(200,255,200,255));
     }
+
+    // the ground is always drawn behind everything else, no matter when it was spawned
+    fn draw_order(&self, _id: &ID<Self>, _world: &World) -> f32 {
+        -1.0
+    }
 }
 
 // The generic argument to Actor is the shared state objects receive.
@@ -122,9 +138,12 @@ impl Actor<Vibbit> for Player {
             self.vel = self.vel.clamp(-3.0, 3.0);
         }
 
-        // draw
-        let body = world.get_physics_body(&id).unwrap();
-        let color = if results.touching_below { vibbit::Color::new(200,255,200,255) } else { vibbit::Color::new(200,200,255,255) };
+        self.grounded = results.touching_below;
+    }
+
+    fn draw(&self, id: &ID<Self>, world: &World, vib: &mut Vibbit) {
+        let body = world.get_physics_body(id).unwrap();
+        let color = if self.grounded { vibbit::Color::new(200,255,200,255) } else { vibbit::Color::new(200,200,255,255) };
         vib.draw_rect(body.pos(), 10.0, 10.0, color);
     }
 }
@@ -134,7 +153,7 @@ pub fn main() {
     let mut vib = Vibbit::new(1280, 720, "context_state");
     vib.set_target_fps(60.0);
 
-    let p_id = world.add_actor(Player {vel: 0.0});
+    let p_id = world.add_actor(Player {vel: 0.0, grounded: false});
     let g_id = world.add_actor(Ground {});
 
     for _ in 0..100 {
@@ -155,6 +174,7 @@ pub fn main() {
         vib.gfx_set_camera(camera);
         // Since our actor logic relies on the vibbit context, we need to pass it to the update method.
         world.update_systems(&mut vib);
+        world.draw_systems(&mut vib);
         vib.end_frame();
 
         if vib.should_close() ||  pos.y < -1000.0 { break; }
@@ -3,7 +3,7 @@ use glam::Vec2;
 use rapidhash::{HashSetExt, RapidHashSet};
 use smallvec::SmallVec;
 
-use crate::{entity::{ID, TypedID}, physics::{PhysicsBody, PhysicsClass}, world::{Registry, World}};
+use crate::{entity::{ID, MessageHandlers, TypedID}, events::SubscriptionHandle, physics::{PhysicsBody, PhysicsClass, TileHit}, shapes::Contact, world::World};
 
 pub trait Actor<P: 'static> where Self: 'static, Self: Sized {
     fn update(&mut self, id: &ID<Self>, world: &mut World, ctx: &mut P) where Self: Sized;
@@ -14,26 +14,50 @@ pub trait Actor<P: 'static> where Self: 'static, Self: Sized {
         PhysicsBody::new(Vec2::ZERO, None, id, crate::physics::PhysicsClass::Node)
     }
 
+    // Draws the actor. Called by `world.draw_systems()`, separately from `update`
+    fn draw(&self, _id: &ID<Self>, _world: &World, _ctx: &mut P) {
+        // user override
+    }
+
+    // Sort key for the draw pass: actors with lower values are drawn first (behind).
+    // Return a constant for z-index style layering, or ie. `-world.get_pos(id).y` to y-sort
+    // with +Y up, as `move_and_slide` assumes, so actors lower on screen are drawn in front.
+    // Rendering with +Y down, use `world.get_pos(id).y` instead.
+    fn draw_order(&self, _id: &ID<Self>, _world: &World) -> f32 {
+        0.0
+    }
+
     #[inline]
     // System that updates the actor's state each frame, applying lifecycle hooks
     fn update_system(world: &mut World, ctx: &mut P) where Self: Sized {
-        let registry_entry = &mut Registry::get_entry_mut::<Self>();
+        // a time scale of zero pauses the type, its bodies stay in the physics tree
+        let time_scale = world.time_scale_of::<Self>();
+        if time_scale == 0.0 { return }
+
         // clone prevents flicker, ie objects spawning in the same frame
-        let entities = registry_entry.entities.to_vec();
+        let Some(entities) = world.registry.get_entry::<Self>().map(|entry| entry.entities.to_vec()) else { return };
+        world.set_current_time_scale(time_scale);
 
         for id in &entities {
-            let entry = registry_entry.arena.get_mut(id.index);
+            // safety: actors are only removed by commands, which are applied between systems,
+            // and only stored between hooks, so the actor stays in place while its hooks run.
+            // only the actor is borrowed, the rest of its type can still be read through the world
+            let entry = unsafe { world.registry.get_mut_detached(id) };
 
             if let Some(actor) = entry {
                 world.current_actor = Some(TypedID::from_id(actor.0));
                 // late collision lifecycle hook         
                 if let Some(collisions) = world.physics.get_late_collision_enter(id) {
                     let overlap_list = world.physics.get_overlap_list(id).clone();
-                    world.physics.update_overlap_list(id, &collisions, &[]);
-                    for collided in collisions {
+                    let collided_ids: SmallVec<[TypedID; 8]> = collisions.iter().map(|(other, _)| *other).collect();
+                    world.physics.update_overlap_list(id, &collided_ids, &[]);
+                    for (collided, contact) in collisions {
                         // prevent double collision events if object already collided last frame
                         if overlap_list.contains(&collided) { continue; }
                         actor.1.on_collision(id, collided, world);
+                        if let Some(contact) = contact {
+                            actor.1.on_contact(id, collided, contact, world);
+                        }
                     }
                 }
                 // late collision end lifecycle hook
@@ -51,6 +75,7 @@ pub trait Actor<P: 'static> where Self: 'static, Self: Sized {
                 println!("perhaps already in use?");
             }
         }
+        world.set_current_time_scale(world.time_scale());
     }
     
     #[inline(always)]
@@ -89,16 +114,65 @@ pub trait Actor<P: 'static> where Self: 'static, Self: Sized {
         world.move_and_slide(*id, vector)
     }
 
+    #[inline(always)]
+    // Moves this solid by the given vector, carrying and pushing actors, see `World::move_solid`
+    fn move_solid(&mut self, vector: &Vec2, world: &mut World) -> Vec2 where Self: Sized {
+        let id = &ID::<Self>::from_typed_id(world.current_actor.unwrap());
+        world.move_solid(*id, vector)
+    }
+
+    // Lifecycle hook: called once, right after the actor is added to the world
+    fn on_spawn(&mut self, _id: &ID<Self>, _world: &mut World) {
+        // user override
+    }
+
+    // Lifecycle hook: called once, when the actor is removed from the world.
+    // The actor is no longer in the registry, but its physics body can still be read.
+    fn on_remove(&mut self, _id: &ID<Self>, _world: &mut World) {
+        // user override
+    }
+
+    // Lists the message types the actor implements `Handles` for, so that messages sent to
+    // its `TypedID` with `world.send_dyn()` reach it. Called once, when the type is registered
+    fn message_handlers(_handlers: &mut MessageHandlers<Self>) {
+        // user override
+    }
+
     // Lifecycle hook: called when the actor enters a collision with another actor
     fn on_collision(&mut self, _id: &ID<Self>, _other: TypedID, _world: &mut World) {
         // user override
     }
 
+    // Lifecycle hook: called right after `on_collision`, with the contact between the two bodies.
+    // The contact normal points from the other body towards this actor.
+    fn on_contact(&mut self, _id: &ID<Self>, _other: TypedID, _contact: Contact, _world: &mut World) {
+        // user override
+    }
+
+    // Lifecycle hook: called right after `on_collision` with an actor that has a tilemap,
+    // once for every tile the actor overlaps. Ie. for spikes, water or ladders.
+    fn on_tile_collision(&mut self, _id: &ID<Self>, _tilemap: TypedID, _tile: TileHit, _world: &mut World) {
+        // user override
+    }
+
     // Lifecycle hook: called when the actor leaves a collision with another actor
     fn on_collision_end(&mut self, _id: &ID<Self>, _other: TypedID, _world: &mut World) {
         // user override
     }
 
+    // Lifecycle hook: called when a solid moved with `world.move_solid()` pushes the actor
+    // into another solid, crushing it between the two. Ie. remove the actor, or kill the player.
+    fn on_squish(&mut self, _id: &ID<Self>, _solid: TypedID, _world: &mut World) {
+        // user override
+    }
+
+    // Return true while the actor holds on to a wall, ie. when climbing. Solids moved with
+    // `world.move_solid()` then carry the actor along when it touches their sides.
+    // Not asked while one of the actor's own hooks is moving the solid.
+    fn is_clinging(&self, _id: &ID<Self>, _world: &World) -> bool {
+        false
+    }
+
     // Returns a list of all actors that are currently colliding with this actor
     fn get_colliding_bodies<'a>(&mut self, world: &'a World) -> &'a RapidHashSet<TypedID> {
         let id = &ID::<Self>::from_typed_id(world.current_actor.unwrap());
@@ -113,14 +187,16 @@ pub trait Actor<P: 'static> where Self: 'static, Self: Sized {
     }
 
     #[inline(always)]
-    // Moves the actor by the given vector, returning the new position
-    fn listen<E: 'static, O: 'static>(&mut self, world: &mut World, other: ID<O>, closure: impl Fn(&mut World, &E) + 'static) {
+    // Subscribes this actor to events emitted by another actor. The subscription
+    // is dropped automatically when either actor is removed.
+    fn listen<E: 'static, O: 'static>(&mut self, world: &mut World, other: ID<O>, closure: impl Fn(&mut World, &E) + 'static) -> SubscriptionHandle {
         let id = &ID::<Self>::from_typed_id(world.current_actor.unwrap());
-        world.subscribe(other, *id, closure);
+        world.subscribe(other, *id, closure)
     }
 
 }
 
+#[derive(Clone, Copy)]
 pub struct MovementResults {
     pub final_pos: Vec2,
 
@@ -128,6 +204,15 @@ pub struct MovementResults {
     pub touching_above: bool,
     pub touching_left: bool,
     pub touching_right: bool,
+
+    /** tile touched on each side, when touching a `TileMapCollider` */
+    pub tile_below: Option<TileHit>,
+    pub tile_above: Option<TileHit>,
+    pub tile_left: Option<TileHit>,
+    pub tile_right: Option<TileHit>,
+
+    /** normal of the surface below the actor when `touching_below`, pointing up */
+    pub ground_normal: Option<Vec2>,
 }
 
 impl World {
@@ -141,6 +226,30 @@ impl World {
         self.physics.get_body(id)
     }
 
+    /**
+    Changes the collision layer and mask of an actor's body, and of its chain edges and tiles.
+    See `PhysicsBody::with_layers`.
+    */
+    pub fn set_collision_layers<T: 'static>(&mut self, id: &ID<T>, layer: u32, mask: u32) {
+        let Some(body) = self.physics.get_body_mut(id) else { return };
+        body.layer = layer;
+        body.mask = mask;
+        let owner = *body;
+        self.physics.sync_chain(&owner);
+        self.physics.sync_tilemap(&owner);
+    }
+
+    /**
+    Lets an actor fall through one-way solids in `move_and_slide` for the next `frames`
+    frames, ie. when the player presses down while standing on a platform.
+    */
+    pub fn drop_through<T: 'static>(&mut self, id: &ID<T>, frames: u32) {
+        let until = self.time().frame() + frames as u64;
+        if let Some(body) = self.physics.get_body_mut(id) {
+            body.drop_through_until = until;
+        }
+    }
+
     pub fn set_pos<T: 'static + Actor<P>, P: 'static>(&mut self, id: ID<T>, pos: Vec2) {
         let mut new_body = *self.physics.get_body(&id).unwrap();
         let old_pos = new_body.pos();
@@ -157,15 +266,31 @@ impl World {
         let bounds = new_body.bounds();
         
         // perform broad phase collision
+        let mut tiles = SmallVec::new();
+        self.physics.query_tiles_against_body(&bounds, &mut tiles, &new_body);
         let mut query = SmallVec::new();
-        self.physics.query_against_id(&bounds, &mut query, id.into_typed_id());
+        self.physics.query_against_body(&bounds, &mut query, &new_body);
+        query.extend(tiles.iter());
+
+        // actors with a chain or tilemap own several bodies, only report them once
+        let mut collided_ids: SmallVec<[TypedID; 4]> = SmallVec::new();
         
-        for collided in query {
+        for collided in &query {
+            if collided_ids.contains(&collided.id) { continue }
             // near phase collision
             if new_body.overlaps(collided) {
                 let other_id = collided.id;
+                collided_ids.push(other_id);
+                let contact = new_body.contact(collided);
+                let tiles = overlapped_tiles(&new_body, other_id, &query);
                 self.with_world(&id, move |ett, world| {
                     ett.on_collision(&id, other_id, world);
+                    if let Some(contact) = contact {
+                        ett.on_contact(&id, other_id, contact, world);
+                    }
+                    for tile in &tiles {
+                        ett.on_tile_collision(&id, other_id, *tile, world);
+                    }
                 });
             }
         }
@@ -187,32 +312,48 @@ impl World {
             self.physics.update_body(&id, new_body);
         }
 
+        let mut tiles = SmallVec::new();
+        self.physics.query_tiles_against_body(&bounds, &mut tiles, &new_body);
         let mut query = SmallVec::new();
-        self.physics.query_against_id(&bounds, &mut query, id.into_typed_id());
+        self.physics.query_against_body(&bounds, &mut query, &new_body);
+        query.extend(tiles.iter());
 
         let overlap_list = self.physics.get_overlap_list(&id);
         // new objects we are overlapping with after movement
         let mut new_overlaps = Vec::with_capacity(4);
+        // contacts with the new overlaps, in the same order
+        let mut new_contacts: Vec<Option<Contact>> = Vec::with_capacity(4);
         // objects we are no longer overlapping with after movement
         let mut overlap_exits = Vec::with_capacity(4);
 
         let mut query_set = RapidHashSet::<TypedID>::with_capacity(query.len());
 
         // lifecycle: collision start
-        for collided in query {
+        for collided in &query {
             query_set.insert(collided.id);
-            if overlap_list.contains(&collided.id) {
+            // actors with a chain or tilemap own several bodies, only report them once
+            if overlap_list.contains(&collided.id) || new_overlaps.contains(&collided.id) {
                 continue; 
             }
             // near phase collision
             if new_body.overlaps(collided) {
                 let other_id = collided.id;
+                let contact = new_body.contact(collided);
                 new_overlaps.push(other_id);
+                new_contacts.push(contact);
+                let tiles = overlapped_tiles(&new_body, other_id, &query);
                 self.with_world(&id, move |ett, world| {
                     ett.on_collision(&id, other_id, world);
+                    if let Some(contact) = contact {
+                        ett.on_contact(&id, other_id, contact, world);
+                    }
+                    for tile in &tiles {
+                        ett.on_tile_collision(&id, other_id, *tile, world);
+                    }
                 });
             }
         }
+        drop(query); // ends borrow of self
 
         // any IDs that are in the overlap list but not in the query are no longer overlapping
         for ov_id in overlap_list {
@@ -221,8 +362,8 @@ impl World {
             }
         }
 
-        for other_id in &new_overlaps {
-            self.physics.add_late_collision_enter(*other_id, id.into_typed_id());
+        for (other_id, contact) in new_overlaps.iter().zip(&new_contacts) {
+            self.physics.add_late_collision_enter(*other_id, id.into_typed_id(), contact.map(|c| c.flipped()));
         }
 
         // lifecycle: collision end
@@ -239,21 +380,114 @@ impl World {
         new_pos
     }
 
+    /**
+    Moves a solid by `delta`, carrying the actors riding it and pushing the actors in its way.
+
+    Actors standing on the solid, or clinging to its sides (see `Actor::is_clinging`), move
+    along with it. Actors it runs into are pushed out of the way with their own `move_and_slide`,
+    and get `Actor::on_squish` if another solid stops them. One-way solids only carry riders.
+    Returns the new position of the solid, as `move_by`.
+    */
+    pub fn move_solid<T: 'static + Actor<P>, P: 'static>(&mut self, id: ID<T>, delta: &Vec2) -> Vec2 {
+        let Some(mut solid) = self.physics.get_body(&id).copied() else {
+            println!("WARNING: move_solid called for an actor without a physics body");
+            return Vec2::ZERO;
+        };
+        if !solid.is_solid() {
+            println!("WARNING: move_solid called for a body that is not a Solid, moving it with move_by");
+            return self.move_by(id, delta);
+        }
+
+        let mut around = solid.bounds();
+        around.expand(delta.abs().max_element() + 1.0);
+        let mut query = SmallVec::new();
+        self.physics.query_filtered(&around, &mut query, |body| body.is_actor() && body.collides_with_layers(&solid));
+        let mut actors: SmallVec<[(PhysicsBody, bool); 8]> = query.iter().map(|body| (**body, false)).collect();
+        drop(query); // ends borrow of self
+
+        // riders are found before anything moves
+        let frame = self.time().frame();
+        for (actor, riding) in actors.iter_mut() {
+            if actor.overlaps(&solid) { continue }
+            let touches = |probe: Vec2| {
+                let mut test_body = *actor;
+                test_body.translate(&probe);
+                test_body.overlaps(&solid)
+            };
+            let dropping = actor.drop_through_until > frame;
+            let stands_on = solid.one_way.is_none_or(|normal| normal.y > 0.0 && !dropping) && touches(Vec2::NEG_Y);
+            let clings = solid.one_way.is_none()
+                && (touches(Vec2::NEG_X) || touches(Vec2::X))
+                && self.registry.vtables.get(&actor.id.type_id).is_some_and(|vtable| (vtable.is_clinging)(self, actor.id));
+            *riding = stands_on || clings;
+        }
+
+        // one axis at a time, so an actor in the way is pushed straight out of it
+        self.moving_solid = Some(solid.id);
+        for step in [Vec2::new(delta.x, 0.0), Vec2::new(0.0, delta.y)] {
+            if step == Vec2::ZERO { continue }
+            let before = solid.pos();
+            solid.translate(&step);
+            // move_and_slide drops fractions of a pixel, so riders follow the whole pixels the
+            // solid crossed. riders that keep sub-pixel movement take the fraction along
+            let crossed = solid.pos().floor() - before.floor();
+
+            for (actor, riding) in actors.iter_mut() {
+                // one-way solids only lift their riders, and never crush them
+                let pushed = solid.one_way.is_none() && actor.overlaps(&solid);
+                let lifted = *riding && actor.overlaps(&solid);
+                let movement = match (pushed || lifted, *riding) {
+                    (true, _) => actor.push_out(&solid, step),
+                    (false, true) if actor.keeps_remainder() => step,
+                    (false, true) if crossed != Vec2::ZERO => crossed,
+                    _ => continue,
+                };
+                let Some(vtable) = self.registry.vtables.get(&actor.id.type_id).copied() else { continue };
+
+                let start = actor.pos();
+                let result = (vtable.move_and_slide)(self, actor.id, movement);
+                actor.set_pos(&result.final_pos);
+
+                // stopped short of getting out of the way
+                let moved = (result.final_pos - start).dot(movement.normalize());
+                if pushed && moved < movement.length() - 0.01 {
+                    self.squish_dyn(actor.id, solid.id);
+                }
+            }
+        }
+        self.moving_solid = None;
+
+        self.move_by(id, delta)
+    }
+
     pub fn move_and_slide<T: 'static + Actor<P>, P: 'static>(&mut self, id: ID<T>, delta: &Vec2) -> MovementResults {
         let actor_body = self.physics.get_body(&id).unwrap();
 
         let start_point = actor_body.pos();
-        let end_point = actor_body.pos() + actor_body.pos_remainder + *delta;
-        let _remainder = end_point - end_point.trunc();
-        // list of pixel positions to check collisions against
-        let movement_steps = crate::math::bresenham_line_movement(start_point, start_point + *delta);
+        // pixel perfect bodies only move whole pixels, and keep the rest for the next move
+        let movement = if actor_body.keeps_remainder() {
+            (actor_body.pos_remainder + *delta).round()
+        } else {
+            *delta
+        };
+        // whole pixels to move, counted from the pixel the actor is on
+        let total = (start_point + movement).as_ivec2().as_vec2() - start_point.as_ivec2().as_vec2();
+
+        // walking along slopes can also rise or drop by up to max_climb per pixel
+        let slope_reach = match actor_body.max_climb() {
+            0 => 0.0,
+            climb => climb as f32 * movement.x.abs().ceil() + 1.0,
+        };
 
         // query area around the actor
         let mut query_bounds = actor_body.bounds();
-        query_bounds.expand(delta.abs().max_element() + 2.0);
+        query_bounds.expand(movement.abs().max_element() + 2.0 + slope_reach);
 
+        let mut tiles = SmallVec::new();
+        self.physics.query_tiles_against_body(&query_bounds, &mut tiles, actor_body);
         let mut query_results = SmallVec::new();
-        self.physics.query_against_id(&query_bounds, &mut query_results, id.into_typed_id());
+        self.physics.query_against_body(&query_bounds, &mut query_results, actor_body);
+        query_results.extend(tiles.iter());
 
         let currently_overlapping = self.physics.get_overlap_list(&id);
         // new objects we are overlapping with after movement
@@ -261,35 +495,71 @@ impl World {
         // objects we are no longer overlapping with after movement
         let mut overlap_exits: Vec<TypedID> = Vec::with_capacity(2);
 
+        // solids within reach of the whole movement. in open space there are none, and
+        // the actor moves straight to the end point
+        let mut end_body = *actor_body;
+        end_body.translate(&total);
+        let mut reach = actor_body.bounds().union(end_body.bounds());
+        reach.expand(slope_reach);
+        let dropping = actor_body.drop_through_until > self.time().frame();
+        let solids: SmallVec<[&PhysicsBody; 4]> = query_results.iter()
+            .filter(|other| other.class == PhysicsClass::Solid && other.bounds().overlaps_aabb(&reach))
+            .filter(|other| !(dropping && other.one_way.is_some()))
+            .filter(|other| self.moving_solid != Some(other.id))
+            .copied()
+            .collect();
+
         let mut final_body = *actor_body;
-        let mut test_body = *actor_body;
-        let mut stopped: bool;
-        // move actor along the line
-        for movement in &movement_steps {
-            // test new location
-            let test_point = final_body.pos() + movement;
-            test_body.set_pos(&test_point);
-
-            // check for collisions
-            stopped = false;    
-            for other_body in &query_results {
-                new_overlaps.push(other_body.id);
-                if other_body.class == PhysicsClass::Solid && test_body.overlaps(other_body) {
-                    stopped = true;
-                }
-            }
+        let mut test_body: PhysicsBody;
+        // standing on the ground before moving, for following it down a slope below
+        let grounded = slope_reach > 0.0 && movement.y <= 0.0 && final_body.ground_normal(&solids).is_some();
+        // sweep to the first solid in the way and slide along it, walking up walkable slopes.
+        // pixel perfect bodies without slopes to walk step a pixel at a time instead
+        if final_body.pixel_perfect && final_body.max_slope <= 0.0 {
+            final_body.step(total, &solids);
+        } else {
+            final_body.slide(total, &solids);
+        }
+        if grounded {
+            final_body.snap_to_ground(slope_reach, &solids);
+        }
 
-            // if not stopped by any collisions, update the final body
-            if !stopped {
-                final_body.set_pos(&test_point);
+        let moved = final_body.pos() - start_point;
+        if final_body.keeps_remainder() {
+            let mut remainder = actor_body.pos_remainder + *delta - movement;
+            // blocked, so the fraction would push into the solid next move
+            if moved.x != movement.x { remainder.x = 0.0 }
+            if moved.y != movement.y { remainder.y = 0.0 }
+            final_body.pos_remainder = remainder;
+        }
+
+        // a moving actor checks every body around it in the overlap list update below
+        if total != Vec2::ZERO {
+            new_overlaps.extend(query_results.iter().map(|other_body| other_body.id));
+        }
+
+        // actors we overlap after movement, with their deepest contact. an actor with a
+        // chain or tilemap owns several bodies, and counts as overlapping if any of them overlaps
+        let mut overlapping: SmallVec<[TypedID; 4]> = SmallVec::new();
+        let mut contacts: SmallVec<[(TypedID, Contact); 4]> = SmallVec::new();
+        for other_body in &query_results {
+            if !final_body.overlaps(other_body) { continue }
+            if !overlapping.contains(&other_body.id) {
+                overlapping.push(other_body.id);
+            }
+            if let Some(contact) = final_body.contact(other_body) {
+                match contacts.iter_mut().find(|(other, _)| *other == other_body.id) {
+                    Some((_, existing)) => if contact.depth > existing.depth { *existing = contact },
+                    None => contacts.push((other_body.id, contact)),
+                }
             }
         }
 
         // update overlap list
         for other_body in &query_results {
-            if final_body.overlaps(other_body) {
+            if overlapping.contains(&other_body.id) {
                 new_overlaps.push(other_body.id);
-            } else {
+            } else if !overlap_exits.contains(&other_body.id) {
                 if new_overlaps.contains(&other_body.id) {
                     overlap_exits.push(other_body.id);
                     // remove from new overlaps
@@ -308,55 +578,92 @@ impl World {
             touching_above: false,
             touching_left: false,
             touching_right: false,
+            tile_below: None,
+            tile_above: None,
+            tile_left: None,
+            tile_right: None,
+            ground_normal: None,
         };
 
-        let actor_bounds = final_body.bounds();
+        // probe one pixel in each direction. this works for any collider shape,
+        // including sloped edges whose bounds say little about where their surface is
         test_body = final_body;
         for other_body in &query_results {
             if final_body.is_actor() && !other_body.is_solid() { continue; }
-
-            let other_bounds = other_body.bounds();
-
-            if actor_bounds.min.y >= other_bounds.max.y && (actor_bounds.min.y - 1.0) <= other_bounds.max.y {
-                test_body.set_pos(&(result.final_pos + Vec2::new(0.0, -1.0)));
-                if test_body.overlaps(other_body) {
-                    result.touching_below = true;
+            if final_body.overlaps(other_body) { continue; }
+            if dropping && other_body.one_way.is_some() { continue; }
+            // one-way solids are only touched from the side they block
+            let blocks = |probe: Vec2| other_body.one_way.is_none_or(|normal| probe.dot(normal) < 0.0);
+
+            test_body.set_pos(&(result.final_pos + Vec2::new(0.0, -1.0)));
+            if blocks(Vec2::NEG_Y) && test_body.overlaps(other_body) {
+                result.touching_below = true;
+                result.tile_below = result.tile_below.or(other_body.tile());
+                // on uneven ground, keep the flattest surface
+                if let Some(contact) = test_body.contact(other_body) {
+                    if result.ground_normal.is_none_or(|normal| contact.normal.y > normal.y) {
+                        result.ground_normal = Some(contact.normal);
+                    }
                 }
             }
-            if actor_bounds.max.y < other_bounds.min.y && (actor_bounds.max.y + 1.0) > other_bounds.min.y {
-                test_body.set_pos(&(result.final_pos + Vec2::new(0.0, 1.0)));
-                if test_body.overlaps(other_body) {
-                    result.touching_above = true;
-                }
+            test_body.set_pos(&(result.final_pos + Vec2::new(0.0, 1.0)));
+            if blocks(Vec2::Y) && test_body.overlaps(other_body) {
+                result.touching_above = true;
+                result.tile_above = result.tile_above.or(other_body.tile());
             }
-            if actor_bounds.min.x > other_bounds.max.x && (actor_bounds.min.x - 1.0) < other_bounds.max.x {
-                test_body.set_pos(&(result.final_pos + Vec2::new(-1.0, 0.0)));
-                if test_body.overlaps(other_body) {
-                    result.touching_left = true;
-                }
+            test_body.set_pos(&(result.final_pos + Vec2::new(-1.0, 0.0)));
+            if blocks(Vec2::NEG_X) && test_body.overlaps(other_body) {
+                result.touching_left = true;
+                result.tile_left = result.tile_left.or(other_body.tile());
             }
-            if actor_bounds.max.x < other_bounds.min.x && (actor_bounds.max.x + 1.0) > other_bounds.min.x {
-                test_body.set_pos(&(result.final_pos + Vec2::new(1.0, 0.0)));
-                if test_body.overlaps(other_body) {
-                    result.touching_right = true;
-                }
+            test_body.set_pos(&(result.final_pos + Vec2::new(1.0, 0.0)));
+            if blocks(Vec2::X) && test_body.overlaps(other_body) {
+                result.touching_right = true;
+                result.tile_right = result.tile_right.or(other_body.tile());
+            }
+        }
+
+        // velocity into a solid is lost. climbing a slope moves further up than asked, and
+        // doesn't count as blocked. resting on the ground does, also between pixel moves
+        if let Some(motion) = final_body.motion.as_mut() {
+            let blocked = |moved: f32, movement: f32| moved * movement.signum() < movement.abs();
+            if motion.velocity.x * movement.x > 0.0 && blocked(moved.x, movement.x) { motion.velocity.x = 0.0 }
+            if motion.velocity.y * movement.y > 0.0 && blocked(moved.y, movement.y) { motion.velocity.y = 0.0 }
+            if (motion.velocity.y < 0.0 && result.touching_below) || (motion.velocity.y > 0.0 && result.touching_above) {
+                motion.velocity.y = 0.0;
             }
         }
 
+        // tiles we overlap after movement, reported along with their tilemap's actor
+        let tiles: SmallVec<[(TypedID, TileHit); 4]> = query_results.iter()
+            .filter(|other_body| final_body.overlaps(other_body))
+            .filter_map(|other_body| Some((other_body.id, other_body.tile()?)))
+            .collect();
+
+        drop(solids);
         drop(query_results); // ends borrow of self
 
-        // may not be necessary
-        new_overlaps.dedup();
+        // every step above pushed its query results, keep the first of each
+        let mut seen = RapidHashSet::<TypedID>::with_capacity(new_overlaps.len());
+        new_overlaps.retain(|other| seen.insert(*other));
         overlap_exits.dedup();
 
         // lifecycle: collision end
         for other_id in &new_overlaps {
             let other_id = *other_id;
+            let contact = contacts.iter().find(|(other, _)| *other == other_id).map(|(_, contact)| *contact);
+            let tiles: SmallVec<[TileHit; 4]> = tiles.iter().filter(|(other, _)| *other == other_id).map(|(_, tile)| *tile).collect();
             self.with_world(&id, move |ett, world| {
                 ett.on_collision(&id, other_id, world);
+                if let Some(contact) = contact {
+                    ett.on_contact(&id, other_id, contact, world);
+                }
+                for tile in &tiles {
+                    ett.on_tile_collision(&id, other_id, *tile, world);
+                }
             });
             // defer collision lifecycle hook on other bodies
-            self.physics.add_late_collision_enter(other_id, id.into_typed_id());
+            self.physics.add_late_collision_enter(other_id, id.into_typed_id(), contact.map(|c| c.flipped()));
         }
 
         // lifecycle: collision end
@@ -375,4 +682,12 @@ impl World {
 
         result
     }
+}
+
+/// Tiles of `other`'s tilemap among `bodies` that `body` overlaps, see `Actor::on_tile_collision`
+fn overlapped_tiles(body: &PhysicsBody, other: TypedID, bodies: &[&PhysicsBody]) -> SmallVec<[TileHit; 4]> {
+    bodies.iter()
+        .filter(|other_body| other_body.id == other && body.overlaps(other_body))
+        .filter_map(|other_body| other_body.tile())
+        .collect()
 }
\ No newline at end of file
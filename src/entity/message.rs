@@ -0,0 +1,63 @@
+use std::any::{Any, TypeId};
+
+use crate::{entity::{ID, TypedID}, world::World};
+
+// delivers a type-erased message to an actor, see `MessageHandlers::add`
+pub(crate) type Deliver = fn(&mut World, TypedID, &dyn Any);
+
+/**
+Handles messages of type `M` sent to the actor with `world.send()`, in the order they were sent.
+
+```ignore
+struct Damage(f32);
+
+impl Handles<Damage> for Enemy {
+    fn on_message(&mut self, id: &ID<Self>, damage: &Damage, world: &mut World) {
+        self.health -= damage.0;
+    }
+}
+```
+
+Messages sent to a `TypedID` with `send_dyn` only arrive if the type is also listed in
+`Actor::message_handlers`.
+*/
+pub trait Handles<M: 'static>: Sized + 'static {
+    fn on_message(&mut self, id: &ID<Self>, message: &M, world: &mut World);
+}
+
+/**
+The message types an actor type handles, filled in by `Actor::message_handlers`
+when the type is registered with a world:
+
+```ignore
+fn message_handlers(handlers: &mut MessageHandlers<Self>) {
+    handlers.add::<Damage>().add::<Heal>();
+}
+```
+*/
+pub struct MessageHandlers<T> {
+    pub(crate) handlers: Vec<(TypeId, Deliver)>,
+    _type: std::marker::PhantomData<fn(T)>,
+}
+
+impl<T: 'static> MessageHandlers<T> {
+    pub(crate) fn new() -> Self {
+        Self { handlers: Vec::new(), _type: std::marker::PhantomData }
+    }
+
+    pub fn add<M: 'static>(&mut self) -> &mut Self where T: Handles<M> {
+        self.handlers.push((TypeId::of::<M>(), deliver::<T, M>));
+        self
+    }
+}
+
+pub(crate) fn deliver<T: Handles<M>, M: 'static>(world: &mut World, id: TypedID, message: &dyn Any) {
+    let Some(message) = message.downcast_ref::<M>() else { return };
+    let id = ID::<T>::from_typed_id(id);
+    // safety: actors are only stored and removed by commands, and commands are never
+    // applied while queued actions run, so the actor stays in place during the hook
+    let Some(entry) = (unsafe { world.registry.get_mut_detached(&id) }) else { return };
+    let previous_actor = world.current_actor.replace(id.into_typed_id());
+    entry.1.on_message(&id, message, world);
+    world.current_actor = previous_actor;
+}
@@ -1,6 +1,9 @@
 mod id;
 mod actor;
+mod message;
 
 pub use id::ID;
 pub use id::TypedID;
-pub use actor::Actor;
\ No newline at end of file
+pub use actor::{Actor, MovementResults};
+pub use message::{Handles, MessageHandlers};
+pub(crate) use message::{Deliver, deliver};
\ No newline at end of file
@@ -0,0 +1,226 @@
+use rapidhash::RapidHashMap;
+use crate::World;
+use crate::events::SubscriptionHandle;
+
+// closure registered with `World::on`
+pub(crate) type HandlerFn<E> = Box<dyn Fn(&mut World, &E)>;
+
+pub(crate) struct Handler<E> {
+    pub(crate) handle: SubscriptionHandle,
+    pub(crate) closure: HandlerFn<E>,
+}
+
+/**
+A world-wide channel for events of type `E`, sent with `World::broadcast`.
+
+Events are double buffered: an event stays readable for the frame it was sent in and the
+whole next frame, so an `EventReader` sees it no matter if its system runs before or after
+the sender's.
+*/
+pub(crate) struct Channel<E> {
+    previous: Vec<E>,
+    current: Vec<E>,
+    // number of events sent before `previous[0]`
+    previous_start: u64,
+    pub(crate) handlers: Vec<Handler<E>>,
+}
+
+impl<E> Channel<E> {
+    fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            previous_start: 0,
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Total number of events ever sent on this channel
+    fn total(&self) -> u64 {
+        self.previous_start + (self.previous.len() + self.current.len()) as u64
+    }
+
+    fn swap_buffers(&mut self) {
+        self.previous_start += self.previous.len() as u64;
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+    }
+
+    fn events_since(&self, index: u64) -> impl Iterator<Item = &E> {
+        let skip = index.saturating_sub(self.previous_start) as usize;
+        self.previous.iter().chain(self.current.iter()).skip(skip)
+    }
+}
+
+/// Storage for every broadcast channel in a world
+pub(crate) struct Channels {
+    map: anymap::AnyMap,
+    // swaps the buffers of each channel type at the end of a frame
+    updaters: Vec<fn(&mut anymap::AnyMap)>,
+    // removes a handler without knowing its event type
+    handler_types: RapidHashMap<SubscriptionHandle, fn(&mut anymap::AnyMap, SubscriptionHandle)>,
+}
+
+impl Default for Channels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Channels {
+    pub fn new() -> Self {
+        Self {
+            map: anymap::AnyMap::new(),
+            updaters: Vec::new(),
+            handler_types: RapidHashMap::default(),
+        }
+    }
+
+    pub fn get<E: 'static>(&self) -> Option<&Channel<E>> {
+        self.map.get::<Channel<E>>()
+    }
+
+    pub fn get_mut<E: 'static>(&mut self) -> &mut Channel<E> {
+        if !self.map.contains::<Channel<E>>() {
+            self.map.insert(Channel::<E>::new());
+            self.updaters.push(|map| {
+                if let Some(channel) = map.get_mut::<Channel<E>>() {
+                    channel.swap_buffers();
+                }
+            });
+        }
+        self.map.get_mut::<Channel<E>>().unwrap()
+    }
+
+    pub fn add_handler<E: 'static>(&mut self, handle: SubscriptionHandle, closure: impl Fn(&mut World, &E) + 'static) {
+        self.get_mut::<E>().handlers.push(Handler { handle, closure: Box::new(closure) });
+        self.handler_types.insert(handle, |map, handle| {
+            if let Some(channel) = map.get_mut::<Channel<E>>() {
+                channel.handlers.retain(|h| h.handle != handle);
+            }
+        });
+    }
+
+    pub fn unsubscribe(&mut self, handle: SubscriptionHandle) -> bool {
+        let Some(remove) = self.handler_types.remove(&handle) else { return false };
+        remove(&mut self.map, handle);
+        true
+    }
+
+    pub fn is_subscribed(&self, handle: SubscriptionHandle) -> bool {
+        self.handler_types.contains_key(&handle)
+    }
+
+    /// Ends the frame for every channel, dropping events older than one frame
+    pub fn update(&mut self) {
+        for update in &self.updaters {
+            update(&mut self.map);
+        }
+    }
+}
+
+/**
+A cursor into a broadcast channel, for systems that poll for events instead of registering a
+handler. Keep one in your actor and call `read` in `update`:
+
+```ignore
+struct Hud { coins: EventReader<CoinCollected>, total: u32 }
+
+fn update(&mut self, id: &ID<Self>, world: &mut World, ctx: &mut ()) {
+    self.total += self.coins.read(world).count() as u32;
+}
+```
+
+Events are retained for one frame after the one they were sent in; a reader that is not read
+for longer than that misses them.
+*/
+pub struct EventReader<E> {
+    last_read: u64,
+    _type: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E: 'static> Default for EventReader<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Clone for EventReader<E> {
+    fn clone(&self) -> Self {
+        Self { last_read: self.last_read, _type: std::marker::PhantomData }
+    }
+}
+
+impl<E: 'static> EventReader<E> {
+    /// Creates a reader that starts with every event still retained by the world
+    pub fn new() -> Self {
+        Self {
+            last_read: 0,
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the events sent since this reader was last read
+    pub fn read<'a>(&mut self, world: &'a World) -> impl Iterator<Item = &'a E> + use<'a, E> {
+        let channel = world.channels.get::<E>();
+        let from = self.last_read;
+        if let Some(channel) = channel {
+            self.last_read = channel.total();
+        }
+        channel.into_iter().flat_map(move |channel| channel.events_since(from))
+    }
+
+    /// Skips every unread event
+    pub fn clear(&mut self, world: &World) {
+        if let Some(channel) = world.channels.get::<E>() {
+            self.last_read = channel.total();
+        }
+    }
+
+    pub fn is_empty(&self, world: &World) -> bool {
+        // events older than the previous frame are gone, even if never read
+        world.channels.get::<E>().is_none_or(|channel| channel.events_since(self.last_read).next().is_none())
+    }
+}
+
+impl World {
+    /**
+    Sends an event to the whole world. Handlers registered with `on` run immediately,
+    and `EventReader`s can read it until the end of the next frame.
+    */
+    pub fn broadcast<E: 'static>(&mut self, event: E) {
+        let handlers = std::mem::take(&mut self.channels.get_mut::<E>().handlers);
+
+        for handler in &handlers {
+            // skip handlers unsubscribed by an earlier handler of this same event
+            if !self.channels.is_subscribed(handler.handle) { continue }
+            (handler.closure)(self, &event);
+        }
+
+        let channels = &mut self.channels;
+        let mut handlers: Vec<_> = handlers.into_iter().filter(|h| channels.handler_types.contains_key(&h.handle)).collect();
+        let channel = channels.get_mut::<E>();
+        handlers.append(&mut channel.handlers);
+        channel.handlers = handlers;
+
+        channel.current.push(event);
+    }
+
+    /**
+    Calls `closure` for every event of type `E` sent with `broadcast`, from any sender.
+    Pass the returned handle to `unsubscribe` to remove it.
+    */
+    pub fn on<E: 'static>(&mut self, closure: impl Fn(&mut World, &E) + 'static) -> SubscriptionHandle {
+        let handle = self.events.new_handle();
+        self.channels.add_handler(handle, closure);
+        handle
+    }
+
+    /// Creates a reader that only sees events broadcast from now on
+    pub fn reader<E: 'static>(&self) -> EventReader<E> {
+        EventReader {
+            last_read: self.channels.get::<E>().map(|channel| channel.total()).unwrap_or(0),
+            _type: std::marker::PhantomData,
+        }
+    }
+}
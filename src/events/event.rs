@@ -1,5 +1,6 @@
 use rapidhash::RapidHashMap;
-use crate::{World, ID};
+use smallvec::SmallVec;
+use crate::{TypedID, World, ID};
 
 pub type Signal<T> = T;
 
@@ -13,11 +14,23 @@ impl<T: 'static> EventEmitter<T> for Signal<T> {
     }
 }
 
-pub struct EventQueue {
-    pub(crate) events: anymap::AnyMap,
+/**
+Handle to a single subscription, returned by `World::subscribe`.
+Pass it to `World::unsubscribe` to stop listening early.
+
+Subscriptions are also dropped automatically when either the emitter or the
+listener is removed from the world.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionHandle(u64);
+
+pub(crate) struct Listener<E> {
+    pub(crate) handle: SubscriptionHandle,
+    pub(crate) closure: Box<dyn Fn(&mut World,&E)>,
 }
+
 pub(crate) struct EventListeners<E> {
-    pub(crate) listeners: Vec<Box<dyn Fn(&mut World,&E)>>,
+    pub(crate) listeners: Vec<Listener<E>>,
 }
 
 impl<E> EventListeners<E> {
@@ -40,6 +53,22 @@ impl<T, E> EventQueueEntry<T, E> {
     }
 }
 
+// bookkeeping for a subscription, so it can be found and removed without knowing its types
+struct Subscription {
+    emitter: TypedID,
+    listener: TypedID,
+    remove: fn(&mut EventQueue, TypedID, SubscriptionHandle),
+}
+
+pub struct EventQueue {
+    pub(crate) events: anymap::AnyMap,
+
+    subscriptions: RapidHashMap<SubscriptionHandle, Subscription>,
+    // every subscription an actor takes part in, as either emitter or listener
+    by_actor: RapidHashMap<TypedID, SmallVec<[SubscriptionHandle; 4]>>,
+    next_handle: u64,
+}
+
 impl Default for EventQueue {
     fn default() -> Self {
         Self::new()
@@ -50,6 +79,9 @@ impl EventQueue {
     pub fn new() -> Self {
         EventQueue {
             events: anymap::AnyMap::new(),
+            subscriptions: RapidHashMap::default(),
+            by_actor: RapidHashMap::default(),
+            next_handle: 0,
         }
     }
 
@@ -67,27 +99,112 @@ impl EventQueue {
         }
     }
 
+    pub(crate) fn new_handle(&mut self) -> SubscriptionHandle {
+        let handle = SubscriptionHandle(self.next_handle);
+        self.next_handle += 1;
+        handle
+    }
+
     pub fn subscribe<E: 'static, T: 'static, L: 'static>
-    (&mut self, emitter: ID<T>, _listener: ID<L>, closure: impl Fn(&mut World, &E) + 'static) {
+    (&mut self, emitter: ID<T>, listener: ID<L>, closure: impl Fn(&mut World, &E) + 'static) -> SubscriptionHandle {
+        let handle = self.new_handle();
+
         let entry = self.get_entry_mut::<T, E>();
+        let listeners = entry.emitters.entry(emitter).or_insert(EventListeners::<E>::new());
+        listeners.listeners.push(Listener { handle, closure: Box::new(closure) });
+
+        let (emitter, listener) = (emitter.into_typed_id(), listener.into_typed_id());
+        self.subscriptions.insert(handle, Subscription {
+            emitter,
+            listener,
+            remove: Self::remove_listener::<E, T>,
+        });
+        self.by_actor.entry(emitter).or_default().push(handle);
+        if listener != emitter {
+            self.by_actor.entry(listener).or_default().push(handle);
+        }
+
+        handle
+    }
+
+    /// Removes a single subscription. Returns false if it was already removed.
+    pub fn unsubscribe(&mut self, handle: SubscriptionHandle) -> bool {
+        let Some(subscription) = self.subscriptions.remove(&handle) else { return false };
+
+        (subscription.remove)(self, subscription.emitter, handle);
+
+        for actor in [subscription.emitter, subscription.listener] {
+            if let Some(handles) = self.by_actor.get_mut(&actor) {
+                handles.retain(|h| *h != handle);
+                if handles.is_empty() {
+                    self.by_actor.remove(&actor);
+                }
+            }
+        }
+        true
+    }
 
-        let listeners = entry.emitters.entry(emitter).or_insert(EventListeners::<E>::new());        
+    /// Removes every subscription the actor takes part in, as either emitter or listener
+    pub fn remove_actor(&mut self, id: TypedID) {
+        let Some(handles) = self.by_actor.remove(&id) else { return };
+        for handle in handles {
+            self.unsubscribe(handle);
+        }
+    }
 
-        listeners.listeners.push(Box::new(closure));
+    pub fn is_subscribed(&self, handle: SubscriptionHandle) -> bool {
+        self.subscriptions.contains_key(&handle)
+    }
+
+    fn remove_listener<E: 'static, T: 'static>(&mut self, emitter: TypedID, handle: SubscriptionHandle) {
+        let emitter = ID::<T>::from_typed_id(emitter);
+        let entry = self.get_entry_mut::<T, E>();
+        if let Some(listeners) = entry.emitters.get_mut(&emitter) {
+            listeners.listeners.retain(|l| l.handle != handle);
+            if listeners.listeners.is_empty() {
+                entry.emitters.remove(&emitter);
+            }
+        }
     }
 
     pub(crate) fn _emit<E: 'static, T: 'static>(&mut self, world: &mut World, emitter: ID<T>, event: E) {
         let entry = self.get_entry_mut::<T, E>();
         if let Some(listeners) = entry.emitters.get_mut(&emitter) {
             for listener in listeners.listeners.iter_mut() {
-                listener(world, &event);
+                (listener.closure)(world, &event);
             }
         }
     }
 
-    pub(crate) fn get_listeners<E: 'static, T: 'static>(&mut self, emitter: ID<T>) -> Option<&mut EventListeners<E>> {
+    /// Temporarily takes the listeners of an emitter, so they can be called with access to the world
+    pub(crate) fn take_listeners<E: 'static, T: 'static>(&mut self, emitter: ID<T>) -> Vec<Listener<E>> {
         let entry = self.get_entry_mut::<T, E>();
-        entry.emitters.get_mut(&emitter)
+        match entry.emitters.get_mut(&emitter) {
+            Some(listeners) => std::mem::take(&mut listeners.listeners),
+            None => Vec::new(),
+        }
+    }
+
+    /**
+    Puts back listeners taken with `take_listeners`. Listeners that were unsubscribed
+    in the meantime are dropped, and listeners added in the meantime are kept after them.
+    */
+    pub(crate) fn restore_listeners<E: 'static, T: 'static>(&mut self, emitter: ID<T>, mut taken: Vec<Listener<E>>) {
+        taken.retain(|l| self.subscriptions.contains_key(&l.handle));
+
+        let entry = self.get_entry_mut::<T, E>();
+        if let Some(listeners) = entry.emitters.get_mut(&emitter) {
+            taken.append(&mut listeners.listeners);
+            listeners.listeners = taken;
+        } else if !taken.is_empty() {
+            entry.emitters.insert(emitter, EventListeners { listeners: taken });
+        }
+
+        if let Some(listeners) = entry.emitters.get(&emitter) {
+            if listeners.listeners.is_empty() {
+                entry.emitters.remove(&emitter);
+            }
+        }
     }
 
 }
\ No newline at end of file
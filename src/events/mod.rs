@@ -1,6 +1,10 @@
 mod event;
 mod event_bus;
+mod broadcast;
 
 pub use event::Signal;
 pub use event::EventQueue;
-pub(crate) use event_bus::EventBus;
\ No newline at end of file
+pub use event::SubscriptionHandle;
+pub(crate) use event_bus::EventBus;
+pub use broadcast::EventReader;
+pub(crate) use broadcast::Channels;
\ No newline at end of file
@@ -0,0 +1,255 @@
+use glam::Vec2;
+use rapidhash::{RapidHashMap, RapidHashSet};
+use slotmap::DefaultKey;
+use smallvec::SmallVec;
+
+use crate::{TypedID, entity::ID, physics::PhysicsBody, world::World};
+
+// penetration left alone, so resting bodies keep touching
+const PENETRATION_SLOP: f32 = 0.1;
+// fraction of the remaining penetration removed every update
+const POSITION_CORRECTION: f32 = 0.4;
+
+// a dynamic body taking part in the physics step
+struct Dynamic {
+    idx: DefaultKey,
+    body: PhysicsBody,
+    velocity: Vec2,
+    inverse_mass: f32,
+    // time step of the body, scaled by the time scale of its type
+    dt: f32,
+}
+
+// contact of dynamic body `a` with body `b`, which is only moved by impulses if it is dynamic too
+struct DynamicContact {
+    a: usize,
+    b: Option<usize>,
+    // velocity of a `b` that impulses don't move, ie. an actor with a `Motion`
+    b_velocity: Vec2,
+    // points from `b` towards `a`
+    normal: Vec2,
+    // negative for a speculative contact, with bodies that don't touch yet but are about to
+    depth: f32,
+    friction: f32,
+    // normal velocity the solver aims for, from the restitution
+    bounce: f32,
+    normal_impulse: f32,
+    tangent_impulse: f32,
+}
+
+impl World {
+    /// Number of passes the solver makes over all contacts between dynamic bodies. Defaults to 8
+    pub fn solver_iterations(&self) -> u32 {
+        self.physics.solver_iterations
+    }
+
+    /// More iterations make stacks of dynamic bodies steadier, at the cost of speed
+    pub fn set_solver_iterations(&mut self, iterations: u32) {
+        self.physics.solver_iterations = iterations;
+    }
+
+    /**
+    Changes the velocity of a dynamic body by `impulse` divided by its mass, ie. for explosions.
+
+    ```ignore
+    world.apply_impulse(&crate_id, (crate_pos - explosion_pos).normalize() * 400.0);
+    ```
+    */
+    pub fn apply_impulse<T: 'static>(&mut self, id: &ID<T>, impulse: Vec2) {
+        let Some(body) = self.physics.get_body_mut(id) else { return };
+        let Some(rigid_body) = body.rigid_body else {
+            println!("WARNING: apply_impulse called for a body without a RigidBody");
+            return;
+        };
+        body.motion.get_or_insert_default().velocity += impulse * rigid_body.inverse_mass();
+    }
+
+    /**
+    Moves every dynamic body and resolves its collisions with impulses, over `dt` seconds.
+    Run by `update_systems` after the actors moved, so they push dynamic bodies this update.
+
+    Bodies about to touch get speculative contacts, which only let them close the gap, so fast
+    bodies stop on thin solids, chains and tiles instead of passing through or sinking in.
+    */
+    pub(crate) fn step_dynamics(&mut self, dt: f32) {
+        let mut bodies: Vec<Dynamic> = Vec::new();
+        for (idx, body) in self.physics.dynamic_bodies() {
+            // paused types stay in place, and other dynamic bodies bounce off them
+            let dt = dt * self.time_scale_of_type(body.id.type_id);
+            if dt == 0.0 { continue }
+            self.physics.integrate_body(idx, dt);
+            let Some(body) = self.physics.get_body_at(idx) else { continue };
+
+            let velocity = body.motion.map(|motion| motion.velocity).unwrap_or(Vec2::ZERO);
+            let inverse_mass = body.rigid_body.map(|rigid_body| rigid_body.inverse_mass()).unwrap_or(0.0);
+            bodies.push(Dynamic { idx, body, velocity, inverse_mass, dt });
+        }
+        if bodies.is_empty() { return }
+
+        let mut contacts = self.find_contacts(&bodies, dt);
+
+        for _ in 0..self.physics.solver_iterations {
+            for contact in contacts.iter_mut() {
+                solve_contact(contact, &mut bodies);
+            }
+        }
+
+        for dynamic in bodies.iter_mut() {
+            dynamic.body.translate(&(dynamic.velocity * dynamic.dt));
+        }
+
+        // speculative contacts stop bodies on the surface, they bounce off it once there
+        for contact in &contacts {
+            if contact.depth < 0.0 && contact.bounce > 0.0 && contact.normal_impulse > 0.0 {
+                bounce(contact, &mut bodies);
+            }
+        }
+
+        // push overlapping bodies apart, impulses only stop them from sinking further
+        for contact in &contacts {
+            let inverse_mass_b = contact.b.map(|b| bodies[b].inverse_mass).unwrap_or(0.0);
+            let total = bodies[contact.a].inverse_mass + inverse_mass_b;
+            if total == 0.0 { continue }
+            let correction = contact.normal * (contact.depth - PENETRATION_SLOP).max(0.0) / total * POSITION_CORRECTION;
+
+            let a = &mut bodies[contact.a];
+            a.body.translate(&(correction * a.inverse_mass));
+            if let Some(b) = contact.b {
+                let b = &mut bodies[b];
+                b.body.translate(&(-correction * b.inverse_mass));
+            }
+        }
+
+        for mut dynamic in bodies {
+            dynamic.body.motion.get_or_insert_default().velocity = dynamic.velocity;
+            self.physics.replace_body(dynamic.idx, dynamic.body);
+        }
+    }
+
+    // contacts of every dynamic body, using the broad phase and the collider contacts
+    fn find_contacts(&self, bodies: &[Dynamic], dt: f32) -> Vec<DynamicContact> {
+        let index: RapidHashMap<TypedID, usize> = bodies.iter().enumerate().map(|(i, dynamic)| (dynamic.body.id, i)).collect();
+        // slower than what gravity adds in one update, the body is resting and doesn't bounce
+        let resting_speed = self.physics.gravity.length() * dt + 1.0;
+
+        let mut contacts = Vec::new();
+        let mut pairs = RapidHashSet::<(usize, usize)>::default();
+        for (a, dynamic) in bodies.iter().enumerate() {
+            let body = &dynamic.body;
+            // everything in the way over the whole update, so fast bodies can't skip past it
+            let mut moved = *body;
+            moved.translate(&(dynamic.velocity * dynamic.dt));
+            let bounds = body.bounds().union(moved.bounds());
+
+            let mut tiles = SmallVec::new();
+            self.physics.query_tiles_against_body(&bounds, &mut tiles, body);
+            let mut query = SmallVec::new();
+            self.physics.query_against_body(&bounds, &mut query, body);
+            query.extend(tiles.iter());
+
+            for other in query {
+                if other.is_node() || other.is_zone() { continue }
+                let b = index.get(&other.id).copied();
+                // pairs of dynamic bodies are solved once, whichever of the two found the other
+                if b.is_some_and(|b| !pairs.insert((a.min(b), a.max(b)))) { continue }
+
+                let (b_velocity, other_rigid) = match b {
+                    Some(b) => (bodies[b].velocity, bodies[b].body.rigid_body),
+                    None => (other.motion.map(|motion| motion.velocity).unwrap_or(Vec2::ZERO), other.rigid_body),
+                };
+                let (normal, depth) = match body.contact(other) {
+                    Some(contact) => (contact.normal, contact.depth),
+                    // not touching yet, but maybe by the end of the update
+                    None => match speculative_contact(body, other, (dynamic.velocity - b_velocity) * dynamic.dt) {
+                        Some(contact) => contact,
+                        None => continue,
+                    },
+                };
+                // one-way solids only hold up bodies on the side they block
+                if other.one_way.is_some_and(|one_way| normal.dot(one_way) <= 0.0) { continue }
+                let rigid_body = body.rigid_body.unwrap_or_default();
+                let other_rigid = other_rigid.unwrap_or(rigid_body);
+
+                let normal_speed = (dynamic.velocity - b_velocity).dot(normal);
+                let restitution = rigid_body.restitution.max(other_rigid.restitution);
+                let bounce = if normal_speed < -resting_speed { -restitution * normal_speed } else { 0.0 };
+
+                contacts.push(DynamicContact {
+                    a,
+                    b,
+                    b_velocity,
+                    normal,
+                    depth,
+                    friction: (rigid_body.friction * other_rigid.friction).sqrt(),
+                    bounce,
+                    normal_impulse: 0.0,
+                    tangent_impulse: 0.0,
+                });
+            }
+        }
+        contacts
+    }
+}
+
+/**
+Contact normal and gap, as a negative depth, of `body` with `other` if it touches it moving by
+`motion`. The gap is measured along the normal, like the depth of an overlap.
+*/
+fn speculative_contact(body: &PhysicsBody, other: &PhysicsBody, motion: Vec2) -> Option<(Vec2, f32)> {
+    let length = motion.length();
+    if length == 0.0 { return None }
+    let dir = motion / length;
+    let (distance, normal) = body.sweep(other, dir)?;
+    if distance > length || dir.dot(normal) >= 0.0 { return None }
+    Some((normal, distance * dir.dot(normal)))
+}
+
+/**
+One pass of sequential impulses over a contact. The impulses add up over the passes, and
+are clamped in total: the normal impulse only pushes, the friction is bound by the normal impulse.
+*/
+fn solve_contact(contact: &mut DynamicContact, bodies: &mut [Dynamic]) {
+    let (a, b, b_velocity) = (contact.a, contact.b, contact.b_velocity);
+    let inverse_mass_a = bodies[a].inverse_mass;
+    let inverse_mass_b = b.map(|b| bodies[b].inverse_mass).unwrap_or(0.0);
+    let total = inverse_mass_a + inverse_mass_b;
+    if total == 0.0 { return }
+
+    let velocity_b = |bodies: &[Dynamic]| b.map(|b| bodies[b].velocity).unwrap_or(b_velocity);
+    let apply = |bodies: &mut [Dynamic], impulse: Vec2| {
+        bodies[a].velocity += impulse * inverse_mass_a;
+        if let Some(b) = b {
+            bodies[b].velocity -= impulse * inverse_mass_b;
+        }
+    };
+
+    // a speculative contact lets the bodies close the gap between them this update, but no more
+    let target = if contact.depth < 0.0 { contact.depth / bodies[a].dt } else { contact.bounce };
+    let relative = bodies[a].velocity - velocity_b(bodies);
+    let impulse = (target - relative.dot(contact.normal)) / total;
+    let normal_impulse = (contact.normal_impulse + impulse).max(0.0);
+    apply(bodies, contact.normal * (normal_impulse - contact.normal_impulse));
+    contact.normal_impulse = normal_impulse;
+
+    let tangent = contact.normal.perp();
+    let relative = bodies[a].velocity - velocity_b(bodies);
+    let impulse = -relative.dot(tangent) / total;
+    let limit = contact.friction * contact.normal_impulse;
+    let tangent_impulse = (contact.tangent_impulse + impulse).clamp(-limit, limit);
+    apply(bodies, tangent * (tangent_impulse - contact.tangent_impulse));
+    contact.tangent_impulse = tangent_impulse;
+}
+
+// sets the speed of a speculative contact's bodies apart to its bounce, after they met
+fn bounce(contact: &DynamicContact, bodies: &mut [Dynamic]) {
+    let inverse_mass_a = bodies[contact.a].inverse_mass;
+    let inverse_mass_b = contact.b.map(|b| bodies[b].inverse_mass).unwrap_or(0.0);
+    let velocity_b = contact.b.map(|b| bodies[b].velocity).unwrap_or(contact.b_velocity);
+    let relative = bodies[contact.a].velocity - velocity_b;
+    let impulse = contact.normal * (contact.bounce - relative.dot(contact.normal)).max(0.0) / (inverse_mass_a + inverse_mass_b);
+
+    bodies[contact.a].velocity += impulse * inverse_mass_a;
+    if let Some(b) = contact.b {
+        bodies[b].velocity -= impulse * inverse_mass_b;
+    }
+}
@@ -0,0 +1,70 @@
+use glam::Vec2;
+
+use crate::{entity::{ID, MovementResults}, world::World};
+
+impl World {
+    /// Gravity of bodies with a `Motion`, in pixels per second squared. Zero unless set
+    pub fn gravity(&self) -> Vec2 {
+        self.physics.gravity
+    }
+
+    /**
+    Sets the gravity of bodies with a `Motion`, scaled by their `gravity_scale`.
+
+    ```ignore
+    world.set_gravity(vec2(0.0, -900.0));
+    ```
+    */
+    pub fn set_gravity(&mut self, gravity: Vec2) {
+        self.physics.gravity = gravity;
+    }
+
+    /// Velocity of a body in pixels per second, zero for bodies without a `Motion`
+    pub fn velocity<T: 'static>(&self, id: &ID<T>) -> Vec2 {
+        self.physics.get_body(id).and_then(|body| body.motion).map(|motion| motion.velocity).unwrap_or(Vec2::ZERO)
+    }
+
+    /// Sets the velocity of a body, giving it a `Motion` if it has none, see `PhysicsBody::with_velocity`
+    pub fn set_velocity<T: 'static>(&mut self, id: &ID<T>, velocity: Vec2) {
+        if let Some(body) = self.physics.get_body_mut(id) {
+            body.motion.get_or_insert_default().velocity = velocity;
+        }
+    }
+
+    /// Sets the acceleration of a body, giving it a `Motion` if it has none
+    pub fn set_acceleration<T: 'static>(&mut self, id: &ID<T>, acceleration: Vec2) {
+        if let Some(body) = self.physics.get_body_mut(id) {
+            body.motion.get_or_insert_default().acceleration = acceleration;
+        }
+    }
+
+    /**
+    Results of the last move the world made for a body with a `Motion`, ie. to check
+    `touching_below` before jumping. None until the body moved once.
+    */
+    pub fn last_movement<T: 'static>(&self, id: &ID<T>) -> Option<&MovementResults> {
+        self.physics.last_movement.get(&id.into_typed_id())
+    }
+
+    /**
+    Moves every actor body with a `Motion` by its velocity over `dt` seconds, scaled by the
+    time scale of its type. Run by `update_systems` after all actors updated.
+    */
+    pub(crate) fn integrate_bodies(&mut self, dt: f32) {
+        let moving = self.physics.moving_bodies();
+
+        // velocities first, moving a body changes its index
+        let mut movements = Vec::with_capacity(moving.len());
+        for (idx, id) in moving {
+            let scale = self.time_scale_of_type(id.type_id);
+            if scale == 0.0 { continue }
+            movements.push((id, self.physics.integrate_body(idx, dt * scale)));
+        }
+
+        for (id, movement) in movements {
+            let Some(vtable) = self.registry.vtables.get(&id.type_id).copied() else { continue };
+            let result = (vtable.move_and_slide)(self, id, movement);
+            self.physics.last_movement.insert(id, result);
+        }
+    }
+}
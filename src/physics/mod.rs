@@ -2,10 +2,19 @@ mod physics;
 pub mod quadtree;
 pub mod dynamictree;
 mod physicsbody;
+mod tilemap;
+mod raycast;
+mod shapecast;
+mod integrate;
+mod dynamics;
 
 pub(crate) use physics::{Physics};
-pub use physicsbody::PhysicsBody;
+pub use physicsbody::{Motion, PhysicsBody, RigidBody};
 pub use physicsbody::PhysicsClass;
+pub use physicsbody::{DEFAULT_LAYER, ALL_LAYERS};
+pub use raycast::RayHit;
+pub use shapecast::ShapeHit;
+pub use tilemap::{Tile, TileHit, TileMapCollider, TileShape, TILE_SOLID, TILE_ONE_WAY};
 
 pub(crate) static TREE_BOUNDS_PADDING: f32 = 4.0;
 
@@ -6,8 +6,9 @@ use anymap::AnyMap;
 use glam::Vec2;
 use slotmap::{DefaultKey, SecondaryMap, SlotMap};
 use smallvec::SmallVec;
-use crate::{ID, TypedID, physics::{dynamictree::DynamicTree, quadtree::QuadTree}, shapes::AABB};
+use crate::{ID, TypedID, entity::MovementResults, physics::{dynamictree::DynamicTree, quadtree::QuadTree}, shapes::{AABB, Collider, Contact, Edge}};
 use crate::physics::physicsbody::PhysicsBody;
+use crate::physics::tilemap::TileMapEntry;
 
 pub struct PhyysicsEntry<T> {
     // source of truth for each body's index in the physics_bodies slotmap
@@ -18,18 +19,35 @@ pub struct PhyysicsEntry<T> {
     pub _type: std::marker::PhantomData<T>,
 }
 
+/// collisions caused by other bodies moving, with the contact from the receiving body's point of view
+pub(crate) type LateCollisions = SmallVec<[(TypedID, Option<Contact>); 8]>;
+
 pub(crate) struct Physics {
     physics_bodies: SlotMap<slotmap::DefaultKey, PhysicsBody>,
     entities: AnyMap,
 
     tree: QuadTree<slotmap::DefaultKey>,
+    // chain edges never move, so they get their own tree that is only rebuilt when a chain changes
+    static_tree: QuadTree<slotmap::DefaultKey>,
     to_delete: SecondaryMap<slotmap::DefaultKey, ()>,
 
+    // extra bodies owned by an actor, one per edge of its chain
+    chains: RapidHashMap<TypedID, Vec<slotmap::DefaultKey>>,
+    // tiles are not in the tree, they are looked up by cell when querying
+    pub(crate) tilemaps: Vec<TileMapEntry>,
+
     // late collision detection. consumed by an object when it updates for events created by other object movement
-    pub late_collision_enter: RapidHashMap<TypedID, SmallVec<[TypedID; 8]>>,
+    pub late_collision_enter: RapidHashMap<TypedID, LateCollisions>,
     // late collision detection. consumed by an object when it updates for events created by other object movement
     pub late_collision_exit: RapidHashMap<TypedID, SmallVec<[TypedID; 8]>>,
 
+    // acceleration of bodies with a `Motion`, see `World::set_gravity`
+    pub(crate) gravity: Vec2,
+    // results of the last move of each body with a `Motion`, see `World::last_movement`
+    pub(crate) last_movement: RapidHashMap<TypedID, MovementResults>,
+    // passes over all contacts when solving dynamic bodies, see `World::set_solver_iterations`
+    pub(crate) solver_iterations: u32,
+
     tree_bounds: AABB,
     queries_last_frame: RefCell<u64>,
 }
@@ -41,12 +59,20 @@ impl Physics {
             entities: AnyMap::new(),
 
             tree: QuadTree::new(size.width(), size.height(), 12),
+            static_tree: QuadTree::new(0.0, 0.0, 12),
 
             to_delete: SecondaryMap::new(),
 
+            chains: RapidHashMap::default(),
+            tilemaps: Vec::new(),
+
             late_collision_enter: RapidHashMap::default(),
             late_collision_exit: RapidHashMap::default(),
 
+            gravity: Vec2::ZERO,
+            last_movement: RapidHashMap::default(),
+            solver_iterations: 8,
+
             tree_bounds: size,
             queries_last_frame: std::cell::RefCell::new(0),
         }
@@ -72,7 +98,7 @@ impl Physics {
         list.retain(|existing| !exit_list.contains(existing));
     }
 
-    pub(crate) fn get_late_collision_enter<T: 'static>(&mut self, id: &ID<T>) -> Option<SmallVec<[TypedID; 8]>> {
+    pub(crate) fn get_late_collision_enter<T: 'static>(&mut self, id: &ID<T>) -> Option<LateCollisions> {
         // consume the list to return it
         self.late_collision_enter.remove(&id.into_typed_id())
     }
@@ -81,9 +107,9 @@ impl Physics {
         self.late_collision_exit.remove(&id.into_typed_id())
     }
 
-    pub(crate) fn add_late_collision_enter(&mut self, id: TypedID, other: TypedID) {
+    pub(crate) fn add_late_collision_enter(&mut self, id: TypedID, other: TypedID, contact: Option<Contact>) {
         let list = self.late_collision_enter.entry(id).or_default();
-        list.push(other);
+        list.push((other, contact));
     }
     pub(crate) fn add_late_collision_exit(&mut self, id: TypedID, other: TypedID) {
         let list = self.late_collision_exit.entry(id).or_default();
@@ -172,6 +198,112 @@ impl Physics {
 
         self.physics_bodies.remove(*idx);
         self.to_delete.insert(*idx, ());
+
+        self.remove_chain(id.into_typed_id());
+        self.remove_tilemap(id.into_typed_id());
+        self.last_movement.remove(&id.into_typed_id());
+    }
+
+    /// Actor bodies with a `Motion`, by their index in `physics_bodies`
+    pub(crate) fn moving_bodies(&self) -> SmallVec<[(DefaultKey, TypedID); 16]> {
+        self.physics_bodies.iter()
+            .filter(|(_, body)| body.is_actor() && body.motion.is_some())
+            .map(|(idx, body)| (idx, body.id))
+            .collect()
+    }
+
+    /// Dynamic bodies by their index in `physics_bodies`, without the edges of their chains
+    pub(crate) fn dynamic_bodies(&self) -> SmallVec<[(DefaultKey, PhysicsBody); 16]> {
+        self.physics_bodies.iter()
+            .filter(|(_, body)| body.is_dynamic() && body.rigid_body.is_some() && !Self::is_chain_edge(body))
+            .map(|(idx, body)| (idx, *body))
+            .collect()
+    }
+
+    pub(crate) fn get_body_at(&self, idx: DefaultKey) -> Option<PhysicsBody> {
+        self.physics_bodies.get(idx).copied()
+    }
+
+    /**
+    Replaces the body at `idx` without touching the tree, for bodies moved by the physics step.
+    The tree is rebuilt by `cleanup` at the end of the same update.
+    */
+    pub(crate) fn replace_body(&mut self, idx: DefaultKey, body: PhysicsBody) {
+        if let Some(old) = self.physics_bodies.get_mut(idx) {
+            *old = body;
+        }
+    }
+
+    /// Applies acceleration, gravity and damping to a body's velocity, returning its movement over `dt` seconds
+    pub(crate) fn integrate_body(&mut self, idx: DefaultKey, dt: f32) -> Vec2 {
+        let gravity = self.gravity;
+        let Some(motion) = self.physics_bodies.get_mut(idx).and_then(|body| body.motion.as_mut()) else { return Vec2::ZERO };
+        motion.velocity += (motion.acceleration + gravity * motion.gravity_scale) * dt;
+        motion.velocity /= 1.0 + motion.damping * dt;
+        motion.velocity * dt
+    }
+
+    /// Adds a body for every edge, sharing the id, class, layers and one-way direction of `owner`
+    pub fn set_chain(&mut self, owner: &PhysicsBody, edges: impl Iterator<Item = Edge>) {
+        self.remove_chain(owner.id);
+
+        let mut indices = Vec::new();
+        for edge in edges {
+            let mut body = PhysicsBody::new(edge.a, Some(Collider::Chain(edge)), owner.id, owner.class)
+                .with_layers(owner.layer, owner.mask);
+            body.one_way = owner.one_way;
+            body.store_prev_pos();
+            indices.push(self.physics_bodies.insert(body));
+        }
+        self.chains.insert(owner.id, indices);
+        self.rebuild_static_tree();
+    }
+
+    /// Copies the class, layers and one-way direction of `owner` to its edges, after they changed
+    pub(crate) fn sync_chain(&mut self, owner: &PhysicsBody) {
+        let Some(indices) = self.chains.get(&owner.id) else { return };
+        for idx in indices {
+            let Some(edge) = self.physics_bodies.get_mut(*idx) else { continue };
+            edge.class = owner.class;
+            edge.layer = owner.layer;
+            edge.mask = owner.mask;
+            edge.one_way = owner.one_way;
+        }
+    }
+
+    pub fn remove_chain(&mut self, id: TypedID) {
+        let Some(indices) = self.chains.remove(&id) else { return };
+        for idx in indices {
+            self.physics_bodies.remove(idx);
+        }
+        self.rebuild_static_tree();
+    }
+
+    fn rebuild_static_tree(&mut self) {
+        let edges = self.chains.values().flatten().filter_map(|idx| Some((*idx, self.physics_bodies.get(*idx)?.bounds())));
+        let extent = edges.clone().fold(Vec2::ZERO, |extent, (_, bounds)| extent.max(bounds.min.abs()).max(bounds.max.abs()));
+
+        self.static_tree = QuadTree::new(extent.x + 32.0, extent.y + 32.0, 12);
+        for (idx, mut bounds) in edges {
+            bounds.expand(crate::physics::TREE_BOUNDS_PADDING);
+            self.static_tree.insert_with_rebalance(idx, &bounds);
+        }
+    }
+
+    fn is_chain_edge(body: &PhysicsBody) -> bool {
+        matches!(body.get_shape(), Some(Collider::Chain(_)))
+    }
+
+    // both trees, the static one last
+    fn query_trees(&self, bounds: &AABB) -> impl Iterator<Item = &(DefaultKey, AABB)> {
+        self.tree.query(bounds).into_iter().chain(self.static_tree.query(bounds))
+    }
+
+    /// Records the current position of every body, so renderers can interpolate between ticks
+    pub(crate) fn store_prev_positions(&mut self) {
+        for (_idx, body) in self.physics_bodies.iter_mut() {
+            body.store_prev_pos();
+        }
     }
 
     pub fn cleanup(&mut self) {
@@ -184,6 +316,7 @@ impl Physics {
             self.physics_bodies.remove(id);
         }
         for (id, body) in self.physics_bodies.iter() {
+            if Self::is_chain_edge(body) {continue}
             let bounds = body.bounds();
             min_bounds.min = min_bounds.min.min(bounds.min);
             min_bounds.max = min_bounds.max.max(bounds.max);
@@ -201,13 +334,15 @@ impl Physics {
         self.queries_last_frame.replace(0);
     }
 
-    pub fn query<'a>(&'a self, bounds: &AABB, out: &mut SmallVec<[&'a PhysicsBody; 4]>) {
-        let q = self.tree.query(bounds);
+    pub fn query<'a>(&'a self, bounds: &AABB, mask: u32, out: &mut SmallVec<[&'a PhysicsBody; 4]>) {
+        let q = self.query_trees(bounds);
             for (idx, _aabb) in q {
             if self.to_delete.contains_key(*idx) {continue}
             
             if let Some(body) = self.physics_bodies.get(*idx) {
-                out.push(body);
+                if body.layer & mask != 0 {
+                    out.push(body);
+                }
             }
         }
         
@@ -215,7 +350,7 @@ impl Physics {
     }
 
     pub fn query_filtered<'a>(&'a self, bounds: &AABB, out: &mut SmallVec<[&'a PhysicsBody; 4]>, filter: impl Fn(&PhysicsBody) -> bool) {   
-        let q = self.tree.query(bounds);
+        let q = self.query_trees(bounds);
 
         for (idx, _aabb) in q {
             if self.to_delete.contains_key(*idx) {continue}
@@ -230,15 +365,33 @@ impl Physics {
         *self.queries_last_frame.borrow_mut() += 1;
     }
 
-    pub(crate) fn query_against_id<'a>(&'a self, bounds: &AABB, out: &mut SmallVec<[&'a PhysicsBody; 4]>, id: TypedID) {
-        let q = self.tree.query(bounds);
+    /// Bodies whose bounds the ray passes through within `max_dist`. `dir` must be normalized.
+    pub fn query_ray<'a>(&'a self, origin: Vec2, dir: Vec2, max_dist: f32, out: &mut SmallVec<[&'a PhysicsBody; 4]>, filter: impl Fn(&PhysicsBody) -> bool) {
+        let q = self.tree.query_ray(origin, dir, max_dist).into_iter().chain(self.static_tree.query_ray(origin, dir, max_dist));
 
         for (idx, _aabb) in q {
             if self.to_delete.contains_key(*idx) {continue}
-            
+
             if let Some(body) = self.physics_bodies.get(*idx) {
-            if body.id != id {
-                out.push(body);
+                if filter(body) {
+                    out.push(body);
+                }
+            }
+        }
+
+        *self.queries_last_frame.borrow_mut() += 1;
+    }
+
+    /// Queries for bodies that the given body can collide with, based on collision layers
+    pub(crate) fn query_against_body<'a>(&'a self, bounds: &AABB, out: &mut SmallVec<[&'a PhysicsBody; 4]>, body: &PhysicsBody) {
+        let q = self.query_trees(bounds);
+
+        for (idx, _aabb) in q {
+            if self.to_delete.contains_key(*idx) {continue}
+            
+            if let Some(other) = self.physics_bodies.get(*idx) {
+            if other.id != body.id && body.collides_with_layers(other) {
+                out.push(other);
             }
             }
         }
@@ -247,11 +400,81 @@ impl Physics {
     }
 
     pub fn get_debug_info(&self) -> Vec<(usize, AABB)> {
-        self.tree.get_debug_info()
+        let mut info = self.tree.get_debug_info();
+        info.extend(self.static_tree.get_debug_info());
+        info
     }
 }
 
 impl crate::world::World {
+    /**
+    Returns every actor whose collider overlaps the given area, and whose layer is in `mask`.
+    Pass `ALL_LAYERS` to find bodies on any layer.
+    */
+    pub fn query_area(&self, bounds: &AABB, mask: u32) -> SmallVec<[TypedID; 8]> {
+        let mut tiles = SmallVec::new();
+        self.physics.query_tiles(bounds, mask, &mut tiles);
+        let mut query = SmallVec::new();
+        self.physics.query(bounds, mask, &mut query);
+        query.extend(tiles.iter());
+
+        let area = Collider::AABB(*bounds);
+        query.iter()
+            .filter(|body| !body.is_node() && body.get_shape().is_some_and(|shape| shape.overlaps(&area)))
+            .map(|body| body.id)
+            .fold(SmallVec::new(), |mut ids, id| {
+                // tiles and chain edges share their owner's id
+                if !ids.contains(&id) { ids.push(id) }
+                ids
+            })
+    }
+
+    /**
+    Returns the contacts of the actor with every body it currently overlaps.
+    Normals point from the other body towards this actor.
+    */
+    pub fn contacts<T: 'static>(&self, id: &ID<T>) -> SmallVec<[(TypedID, Contact); 4]> {
+        let mut contacts = SmallVec::new();
+        let Some(body) = self.physics.get_body(id) else { return contacts };
+
+        let mut tiles = SmallVec::new();
+        self.physics.query_tiles_against_body(&body.bounds(), &mut tiles, body);
+        let mut query = SmallVec::new();
+        self.physics.query_against_body(&body.bounds(), &mut query, body);
+        query.extend(tiles.iter());
+        for other in query {
+            if let Some(contact) = body.contact(other) {
+                contacts.push((other.id, contact));
+            }
+        }
+        contacts
+    }
+
+    /**
+    Gives an actor a static chain of connected edges, for terrain like hills and slopes.
+    With `closed` set, the last point connects back to the first.
+
+    Every edge is its own body in the broad phase, so long chains stay cheap to query.
+    The edges use the class, collision layers and `one_way` direction of the actor's body,
+    and collisions with them are reported with the actor's id. Points are in world space and
+    do not follow the actor. Calling this again replaces the previous chain.
+
+    ```ignore
+    // the terrain actor's own body is a `Solid` with no collider
+    world.set_chain(&hills, &[vec2(0.0, 0.0), vec2(64.0, 16.0), vec2(128.0, 8.0)], false);
+    ```
+    */
+    pub fn set_chain<T: 'static>(&mut self, id: &ID<T>, points: &[Vec2], closed: bool) {
+        let Some(owner) = self.physics.get_body(id).copied() else {
+            println!("WARNING: set_chain called for an actor without a physics body");
+            return;
+        };
+
+        let count = if closed && points.len() > 2 { points.len() } else { points.len().saturating_sub(1) };
+        let edges = (0..count).map(|i| Edge::new(points[i], points[(i + 1) % points.len()]));
+        self.physics.set_chain(&owner, edges);
+    }
+
     pub fn get_colliding_bodies<T: 'static>(&self, id: &ID<T>) -> &RapidHashSet<TypedID> {
         self.physics.get_overlap_list(id)
     }
@@ -1,6 +1,19 @@
 use glam::Vec2;
+use smallvec::SmallVec;
 
-use crate::{TypedID, physics::HasBounds, shapes::{Collider, Shape}};
+use crate::{TypedID, physics::{HasBounds, TileHit}, shapes::{Collider, Contact, Shape}};
+
+/** layer every body is on by default */
+pub const DEFAULT_LAYER: u32 = 1;
+/** mask that collides with every layer, used by default */
+pub const ALL_LAYERS: u32 = u32::MAX;
+
+// surfaces `slide` follows in one move, ie. the floor and then a wall
+const MAX_SLIDES: usize = 4;
+// gap `slide` leaves between the body and what it hits, to keep float error from sinking it in
+const SLIDE_SKIN: f32 = 0.001;
+// distance to a whole pixel that `slide` still rounds onto
+const SNAP_DISTANCE: f32 = 0.01;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum PhysicsClass {
@@ -8,6 +21,63 @@ pub enum PhysicsClass {
     Solid,
     Zone,
     Node,
+    /** moved by the world, bouncing off other bodies with impulses. See `RigidBody` */
+    Dynamic,
+}
+
+/**
+Velocity of a body, integrated and applied with `move_and_slide` by `update_systems` after
+every actor updated. Only actor bodies are integrated. See `PhysicsBody::with_velocity`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    /** in pixels per second. zeroed on an axis when movement along it is blocked */
+    pub velocity: Vec2,
+    /** in pixels per second squared, added to the velocity every update */
+    pub acceleration: Vec2,
+    /** multiplier for `World::gravity`, 0 for a body that doesn't fall */
+    pub gravity_scale: f32,
+    /** linear damping, the higher the faster the body slows down. 0 keeps the velocity */
+    pub damping: f32,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self { velocity: Vec2::ZERO, acceleration: Vec2::ZERO, gravity_scale: 1.0, damping: 0.0 }
+    }
+}
+
+/**
+Mass and surface of a `PhysicsClass::Dynamic` body, ie. crates and debris. Dynamic bodies
+move by their `Motion` and collide with every other body, but don't rotate.
+
+Actors are not blocked by dynamic bodies, they push them out of the way instead. Solids,
+actors and tiles don't move from impulses, as if their mass was infinite.
+
+Collision hooks only come from actors moving: an actor moving into a dynamic body calls
+`on_collision` on both, but dynamic bodies moving on their own don't call any hooks, don't
+overlap zones and are not in overlap lists. Use `World::query_area` to find them instead.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RigidBody {
+    /** 0 makes the body immovable, like a solid */
+    pub mass: f32,
+    /** bounciness, from 0 for none to 1 for bouncing back at full speed */
+    pub restitution: f32,
+    /** from 0 for ice upwards, combined with the friction of the other body */
+    pub friction: f32,
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        Self { mass: 1.0, restitution: 0.1, friction: 0.4 }
+    }
+}
+
+impl RigidBody {
+    pub(crate) fn inverse_mass(&self) -> f32 {
+        if self.mass > 0.0 { 1.0 / self.mass } else { 0.0 }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -17,31 +87,214 @@ pub struct PhysicsBody {
 
     origin: Vec2,
 
-    /** remaining position data after translation */
+    /** position at the start of the current logic tick, used for render interpolation */
+    prev_pos: Vec2,
+
+    /** fraction of a pixel left over by `move_and_slide`, for `pixel_perfect` bodies */
     pub(crate) pos_remainder: Vec2,
 
     pub id: TypedID,
     pub class: PhysicsClass,
-    
+
+    /** collision layers this body is on, as a bit mask */
+    pub layer: u32,
+    /** collision layers this body collides with, as a bit mask */
+    pub mask: u32,
+
+    /**
+    Makes `move_and_slide` move this body by whole pixels only, keeping it on integral
+    positions. The fraction of each move is carried over to the next one, so slow speeds
+    still add up to movement. See `with_pixel_perfect`.
+    */
+    pub pixel_perfect: bool,
+    /**
+    Steepest slope `move_and_slide` walks up and down, in radians from flat ground.
+    0 turns slope handling off, so any solid in the way blocks. See `with_max_slope`.
+    */
+    pub max_slope: f32,
+    /**
+    Velocity integrated by the world, none for bodies that are only moved by hand.
+    Set by `with_velocity`, `with_acceleration`, `with_gravity_scale` or `with_damping`.
+    */
+    pub motion: Option<Motion>,
+    /** mass, restitution and friction, set for `PhysicsClass::Dynamic` bodies */
+    pub rigid_body: Option<RigidBody>,
+
+    /**
+    Makes a solid one-way: it only blocks bodies moving against this direction, coming from
+    outside of it. `Vec2::Y` is a platform that can be jumped through from below and stood on.
+    */
+    pub one_way: Option<Vec2>,
+    /** frame until which `move_and_slide` ignores one-way solids, see `World::drop_through` */
+    pub(crate) drop_through_until: u64,
+
+    /** set on bodies built for a tile of a `TileMapCollider` */
+    pub(crate) tile: Option<TileHit>,
 }
 
 impl PhysicsBody {
     pub fn new(pos: Vec2, body: Option<Collider>, id: TypedID, class: PhysicsClass) -> Self {
-        Self { pos, origin: Vec2::ZERO, pos_remainder: Vec2::ZERO, body, id, class }
+        let mut new = Self { pos, origin: Vec2::ZERO, prev_pos: pos, pos_remainder: Vec2::ZERO, body, id, class, layer: DEFAULT_LAYER, mask: ALL_LAYERS, pixel_perfect: false, max_slope: 0.0, motion: None, rigid_body: None, one_way: None, drop_through_until: 0, tile: None };
+        if class == PhysicsClass::Dynamic {
+            new.motion = Some(Motion::default());
+            new.rigid_body = Some(RigidBody::default());
+        }
+        new
     }
 
     pub fn new_node(id: TypedID) -> Self {
-        Self { pos: Vec2::ZERO, origin: Vec2::ZERO, pos_remainder: Vec2::ZERO, body: None, id, class: PhysicsClass::Node }
+        Self { pos: Vec2::ZERO, origin: Vec2::ZERO, prev_pos: Vec2::ZERO, pos_remainder: Vec2::ZERO, body: None, id, class: PhysicsClass::Node, layer: DEFAULT_LAYER, mask: ALL_LAYERS, pixel_perfect: false, max_slope: 0.0, motion: None, rigid_body: None, one_way: None, drop_through_until: 0, tile: None }
     }
 
     pub fn pos(&self) -> Vec2 {
         self.pos
     }
 
+    /**
+    Sets the collision layers of the body, for use in `init_physicsbody`.
+    Two bodies only collide if each one's mask contains a layer of the other.
+
+    ```ignore
+    const BULLET: u32 = 1 << 1;
+    body.with_layers(BULLET, ALL_LAYERS & !BULLET) // bullets ignore other bullets
+    ```
+    */
+    pub fn with_layers(mut self, layer: u32, mask: u32) -> Self {
+        self.layer = layer;
+        self.mask = mask;
+        self
+    }
+
+    /**
+    Opts into Celeste-style integer movement, for use in `init_physicsbody`. Place the body
+    on a whole pixel, and `move_and_slide` keeps it on one:
+
+    ```ignore
+    // moves 1 pixel every third frame
+    world.move_and_slide(id, &vec2(0.34, 0.0));
+    ```
+
+    The leftover fraction is dropped on an axis when movement along it is blocked. Unless
+    the body also walks slopes, it moves a pixel at a time, slipping around the corners of
+    solids the way the pixel grid does.
+    */
+    pub fn with_pixel_perfect(mut self) -> Self {
+        self.pixel_perfect = true;
+        self
+    }
+
+    /**
+    Lets `move_and_slide` climb and descend slopes up to `max_slope` radians, for use in
+    `init_physicsbody`. While on the ground the body stays snapped to it walking down a slope,
+    unless it moves upwards, ie. when jumping.
+
+    ```ignore
+    body.with_max_slope(50f32.to_radians())
+    ```
+    */
+    pub fn with_max_slope(mut self, max_slope: f32) -> Self {
+        self.max_slope = max_slope;
+        self
+    }
+
+    /**
+    Gives the body a `Motion`, for use in `init_physicsbody`. The world then moves it every
+    update, so the actor only steers it, ie. with `World::set_velocity` to jump.
+    Bodies with a motion keep fractions of a pixel between moves, as `pixel_perfect` bodies do.
+
+    ```ignore
+    body.with_velocity(vec2(0.0, 0.0)).with_damping(0.5)
+    ```
+    */
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.motion.get_or_insert_default().velocity = velocity;
+        self
+    }
+
+    /// Gives the body a `Motion` with a constant acceleration, see `with_velocity`
+    pub fn with_acceleration(mut self, acceleration: Vec2) -> Self {
+        self.motion.get_or_insert_default().acceleration = acceleration;
+        self
+    }
+
+    /// Gives the body a `Motion` that falls by `scale` times the world gravity, see `with_velocity`
+    pub fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.motion.get_or_insert_default().gravity_scale = scale;
+        self
+    }
+
+    /// Gives the body a `Motion` that slows down by `damping`, see `with_velocity`
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.motion.get_or_insert_default().damping = damping;
+        self
+    }
+
+    /// Sets the mass of a dynamic body, see `RigidBody`
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.rigid_body.get_or_insert_default().mass = mass;
+        self
+    }
+
+    /// Sets the bounciness of a dynamic body, see `RigidBody`
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.rigid_body.get_or_insert_default().restitution = restitution;
+        self
+    }
+
+    /// Sets the friction of a dynamic body, see `RigidBody`
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.rigid_body.get_or_insert_default().friction = friction;
+        self
+    }
+
+    /// Makes a solid one-way, see `one_way`
+    pub fn with_one_way(mut self, normal: Vec2) -> Self {
+        self.one_way = Some(normal.normalize_or_zero());
+        self
+    }
+
+    /// Fraction of a pixel that `move_and_slide` will add to the next move of a `pixel_perfect` body
+    pub fn pos_remainder(&self) -> Vec2 {
+        self.pos_remainder
+    }
+
+    /// Returns true if `move_and_slide` keeps the fraction of a pixel left over by a move
+    pub(crate) fn keeps_remainder(&self) -> bool {
+        self.pixel_perfect || self.motion.is_some()
+    }
+
+    /// Returns true if the layers and masks of both bodies allow them to collide
+    #[inline(always)]
+    pub fn collides_with_layers(&self, other: &PhysicsBody) -> bool {
+        (self.mask & other.layer) != 0 && (other.mask & self.layer) != 0
+    }
+
+    /// The tile this body was built for, when it comes from a tilemap query
+    pub fn tile(&self) -> Option<TileHit> {
+        self.tile
+    }
+
     pub fn origin(&self) -> Vec2 {
         self.origin
     }
 
+    /// Position of the body at the start of the current logic tick
+    pub fn prev_pos(&self) -> Vec2 {
+        self.prev_pos
+    }
+
+    /**
+    Blends between the previous and current position.
+    `alpha` of 0.0 returns the previous tick's position, 1.0 returns the current one.
+    */
+    pub fn interpolated_pos(&self, alpha: f32) -> Vec2 {
+        self.prev_pos.lerp(self.pos, alpha)
+    }
+
+    pub(crate) fn store_prev_pos(&mut self) {
+        self.prev_pos = self.pos;
+    }
+
     pub fn get_shape(&self) -> Option<&Collider>{
         self.body.as_ref()
     }    
@@ -127,6 +380,226 @@ impl PhysicsBody {
         }
     }
 
+    /**
+    Moves this body by `movement`, stopping at the first of `solids` in the way and sliding
+    along it for the rest of the movement. Every solid is swept once per slide, and the body
+    only lands on whole pixels at the end, so the cost doesn't grow with the distance moved.
+
+    For whole pixel movements of boxes this gives the same result as moving one pixel at a
+    time, except around the corners of solids: the pixel staircase can slip past a corner
+    that the straight line hits, and turns back towards its original direction once it
+    slides off the end of a solid. `pixel_perfect` bodies use `step` instead, which moves
+    them exactly so. With slope handling, see `slide_along`.
+    */
+    pub(crate) fn slide(&mut self, movement: Vec2, solids: &[&PhysicsBody]) {
+        let start = *self;
+        let mut remaining = movement;
+
+        // a solid the body already overlaps only lets it move out, one axis at a time
+        let stuck_in = |solid: &&&PhysicsBody| self.overlaps(solid);
+        for solid in solids.iter().filter(stuck_in).filter(|solid| solid.one_way.is_none()) {
+            for axis in [Vec2::X, Vec2::Y] {
+                let mut nudged = start;
+                nudged.translate(&(axis * remaining.dot(axis).signum()));
+                if nudged.overlaps(solid) { remaining -= axis * remaining.dot(axis) }
+            }
+        }
+        let solids: SmallVec<[&PhysicsBody; 4]> = solids.iter().filter(|solid| !stuck_in(solid)).copied().collect();
+
+        for _ in 0..MAX_SLIDES {
+            let length = remaining.length();
+            if length < 0.001 { break }
+            let dir = remaining / length;
+
+            let Some((distance, normal, solid)) = self.first_hit(dir, length, &solids) else {
+                self.translate(&remaining);
+                break;
+            };
+            // stop just short of the solid, snapping gets the body onto it
+            let distance = (distance - SLIDE_SKIN).max(0.0);
+            self.translate(&(dir * distance));
+
+            let normal = self.slide_normal(normal, dir, solid);
+            remaining = self.slide_along(dir * (length - distance), normal);
+        }
+
+        // whole pixels, rounding towards the start unless the body is on one already
+        let moved = self.pos() - start.pos();
+        let snap = |moved: f32| if (moved - moved.round()).abs() < SNAP_DISTANCE { moved.round() } else { moved.trunc() };
+        let snapped = Vec2::new(snap(moved.x), snap(moved.y));
+        let sign = |moved: f32| if moved == 0.0 { 0.0 } else { moved.signum() };
+        let away = Vec2::new(0.0, if snapped.y != moved.y { sign(moved.y) } else { 0.0 });
+        let back = Vec2::new(sign(snapped.x), sign(snapped.y));
+
+        // rounding onto a slope or a curved surface can end up a fraction of a pixel inside
+        // it. rounding up gets a body walking up a slope onto it, else it steps back
+        let candidates = [snapped, snapped + away, snapped - back * Vec2::X, snapped - back * Vec2::Y, snapped - back];
+        let free = candidates.into_iter().find(|candidate| {
+            let mut moved = start;
+            moved.translate(candidate);
+            !solids.iter().any(|solid| solid.one_way.is_none() && moved.overlaps(solid))
+        });
+        *self = start;
+        self.translate(&free.unwrap_or(snapped));
+    }
+
+    /**
+    Moves this body by the whole pixels of `movement` one pixel at a time, skipping the steps
+    that would take it into one of `solids`. Costs a check against every solid per pixel, but
+    unlike `slide` also follows the pixel staircase around the corners of solids.
+    */
+    pub(crate) fn step(&mut self, movement: Vec2, solids: &[&PhysicsBody]) {
+        if solids.is_empty() {
+            self.translate(&movement);
+            return;
+        }
+        for step in crate::math::bresenham_line_movement(Vec2::ZERO, movement) {
+            let mut next = *self;
+            next.translate(&step);
+            // one-way solids only stop bodies stepping into them against their normal
+            let blocks = |solid: &&PhysicsBody| next.overlaps(solid) && match solid.one_way {
+                Some(normal) => step.dot(normal) < 0.0 && !self.overlaps(solid),
+                None => true,
+            };
+            if !solids.iter().any(blocks) {
+                *self = next;
+            }
+        }
+    }
+
+    /**
+    What is left of `movement` after touching a surface with `normal`, sliding along it.
+    With slope handling, walkable slopes are walked along keeping the horizontal distance,
+    and steeper ones are not climbed.
+    */
+    fn slide_along(&self, movement: Vec2, normal: Vec2) -> Vec2 {
+        let slid = movement - normal * movement.dot(normal).min(0.0);
+        if self.max_slope <= 0.0 { return slid }
+
+        if self.walkable(normal) {
+            if movement.x == 0.0 { return slid }
+            // the ground holds the body up, only moving up leaves it
+            let along = Vec2::new(normal.y, -normal.x) * (movement.x / normal.y);
+            return along + Vec2::new(0.0, movement.y.max(0.0));
+        }
+        if slid.y > movement.y.max(0.0) {
+            // too steep, the body falls or rises along it without moving into it
+            let vertical = Vec2::new(0.0, movement.y);
+            return vertical - normal * vertical.dot(normal).min(0.0);
+        }
+        slid
+    }
+
+    /**
+    Moves this body down onto walkable ground up to `reach` below it, keeping it on the
+    ground walking down a slope. Ground further down is a ledge, and the body falls instead.
+    */
+    pub(crate) fn snap_to_ground(&mut self, reach: f32, solids: &[&PhysicsBody]) {
+        let Some((distance, normal, _)) = self.first_hit(Vec2::NEG_Y, reach, solids) else { return };
+        if !self.walkable(normal) { return }
+        let drop = if (distance - distance.round()).abs() < SNAP_DISTANCE { distance.round() } else { distance.floor() };
+        self.translate(&(Vec2::NEG_Y * drop));
+    }
+
+    /// Nearest of `solids` in the way of moving `length` along `dir`, with its distance and normal
+    fn first_hit<'a>(&self, dir: Vec2, length: f32, solids: &[&'a PhysicsBody]) -> Option<(f32, Vec2, &'a PhysicsBody)> {
+        let shape = self.body?;
+        let mut nearest = None;
+        for solid in solids {
+            let Some(other) = solid.body else { continue };
+            // one-way solids only stop bodies moving against their normal
+            if solid.one_way.is_some_and(|normal| dir.dot(normal) >= 0.0) { continue }
+
+            let Some((distance, normal)) = shape.sweep(&other, dir) else { continue };
+            if distance >= length || dir.dot(normal) > -0.001 { continue }
+            if solid.one_way.is_some_and(|one_way| normal.dot(one_way) <= 0.0) { continue }
+            // only grazing it, ie. sliding along its surface
+            let mut past = *self;
+            past.translate(&(dir * (distance + SNAP_DISTANCE)));
+            if !past.overlaps(solid) { continue }
+            if nearest.is_none_or(|(nearest, _, _)| distance < nearest) {
+                nearest = Some((distance, normal, *solid));
+            }
+        }
+        nearest
+    }
+
+    /**
+    Normal to slide along after touching `solid`. Boxes meeting corner to corner are blocked
+    vertically, like moving a pixel at a time does by taking the horizontal step first.
+    */
+    fn slide_normal(&self, normal: Vec2, dir: Vec2, solid: &PhysicsBody) -> Vec2 {
+        let axis_aligned = normal.x.abs() > 0.999 || normal.y.abs() > 0.999;
+        if !axis_aligned || dir.x == 0.0 || dir.y == 0.0 { return normal }
+
+        let blocks = |nudge: Vec2| {
+            let mut nudged = *self;
+            nudged.translate(&(nudge * SNAP_DISTANCE));
+            nudged.overlaps(solid)
+        };
+        match (blocks(Vec2::new(dir.x.signum(), 0.0)), blocks(Vec2::new(0.0, dir.y.signum()))) {
+            (true, false) => Vec2::new(-dir.x.signum(), 0.0),
+            (false, _) => Vec2::new(0.0, -dir.y.signum()),
+            (true, true) => normal,
+        }
+    }
+
+    /**
+    Movement along `step` that gets this body out of `solid`, after `solid` moved by `step`
+    into it. Rounded up to whole pixels, as `move_and_slide` moves by.
+    */
+    pub(crate) fn push_out(&self, solid: &PhysicsBody, step: Vec2) -> Vec2 {
+        let (Some(shape), Some(other)) = (self.get_shape(), solid.get_shape()) else { return step };
+        let length = step.length();
+        let dir = step / length;
+
+        // moved along with the solid, the body is clear of it. sweeping back finds the contact
+        let mut ahead = *shape;
+        ahead.translate(step);
+        let push = match ahead.sweep(other, -dir) {
+            Some((distance, _)) if distance < length => length - distance,
+            _ => length,
+        };
+        dir * push.ceil()
+    }
+
+    /// Pixels this body may rise or drop per pixel of horizontal movement, 0 without slope handling
+    pub(crate) fn max_climb(&self) -> u32 {
+        if self.max_slope <= 0.0 { return 0 }
+        (self.max_slope.min(89f32.to_radians()).tan().ceil() as u32).max(1)
+    }
+
+    fn walkable(&self, normal: Vec2) -> bool {
+        normal.angle_to(Vec2::Y).abs() <= self.max_slope + 0.001
+    }
+
+    /// Most upward normal of the `solids` one pixel below this body, if it stands on any
+    pub(crate) fn ground_normal(&self, solids: &[&PhysicsBody]) -> Option<Vec2> {
+        let mut probe = *self;
+        probe.translate(&Vec2::NEG_Y);
+        solids.iter()
+            .filter(|solid| !self.overlaps(solid) && solid.one_way.is_none_or(|normal| normal.y > 0.0))
+            .filter_map(|solid| probe.contact(solid))
+            .map(|contact| contact.normal)
+            .max_by(|a, b| a.y.total_cmp(&b.y))
+    }
+
+    /**
+    Returns the contact between the two bodies if they overlap, from this body's point of view:
+    the normal points from `other` towards this body.
+    */
+    pub fn contact(&self, other: &PhysicsBody) -> Option<Contact> {
+        if self.class == PhysicsClass::Node || other.class == PhysicsClass::Node {
+            return None;
+        }
+        self.body?.contact(&other.body?)
+    }
+
+    /// Distance this body travels along `dir` before touching `other`, with the normal of `other` there
+    pub(crate) fn sweep(&self, other: &PhysicsBody, dir: Vec2) -> Option<(f32, Vec2)> {
+        self.body?.sweep(&other.body?, dir)
+    }
+
     pub fn is_solid(&self) -> bool {
         self.class == PhysicsClass::Solid
     }
@@ -139,6 +612,9 @@ impl PhysicsBody {
     pub fn is_node(&self) -> bool {
         self.class == PhysicsClass::Node
     }
+    pub fn is_dynamic(&self) -> bool {
+        self.class == PhysicsClass::Dynamic
+    }
 
 }
 
@@ -221,6 +221,40 @@ impl<T: Clone> QuadTree<T> where T: Clone, T: Copy {
         out
     }
 
+    /// Elements whose bounds the ray passes through within `max_dist`. `dir` must be normalized.
+    pub fn query_ray<'a>(&'a self, origin: Vec2, dir: Vec2, max_dist: f32) -> SmallVec<[&'a (T, AABB); 16]> {
+        let mut out = smallvec![];
+        let hits = |bounds: &AABB| shapes::solve::ray_aabb(origin, dir, bounds).is_some_and(|(t, _)| t <= max_dist);
+
+        let stack = unsafe { &mut *self.query_stack.get() };
+        unsafe {stack.set_len(0);}
+        stack.push(&self.root as *const Node<T>);
+
+        let mut cursor = 0;
+
+        // only descends into the nodes the ray crosses, so long rays stay cheap
+        while cursor < stack.len() {
+            let node: &'a Node<T> = unsafe { &*stack[cursor] };
+            cursor += 1;
+
+            if let Some(children) = &node.children {
+                for child in children.iter() {
+                    if hits(&child.node_bounds) {
+                        stack.push(child as *const Node<T>);
+                    }
+                }
+            }
+
+            for e in &node.elements {
+                if hits(&e.1) {
+                    out.push(e);
+                }
+            }
+        }
+
+        out
+    }
+
     pub fn len(&self) -> usize {
         self.root.get_total()
     }
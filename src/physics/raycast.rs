@@ -0,0 +1,74 @@
+use glam::Vec2;
+use smallvec::SmallVec;
+
+use crate::{TypedID, physics::{PhysicsBody, TileHit}, world::World};
+
+/// Where a ray hit a body, see `World::raycast`
+#[derive(Clone, Copy)]
+pub struct RayHit {
+    pub id: TypedID,
+    /** point on the surface of the body, in world space */
+    pub point: Vec2,
+    /** surface normal at `point`, facing the ray */
+    pub normal: Vec2,
+    /** distance from the origin of the ray to `point` */
+    pub distance: f32,
+    /** tile that was hit, when the body is a `TileMapCollider` */
+    pub tile: Option<TileHit>,
+}
+
+impl World {
+    /**
+    Returns the first body hit by a ray, for line of sight, hitscan weapons or ground probes.
+
+    Only bodies for which `filter` returns true are tested, so it can skip the caster or pick
+    collision layers. A ray starting inside a body hits it at a distance of 0, with a normal
+    facing back along the ray.
+
+    ```ignore
+    let me = id.into_typed_id();
+    let hit = world.raycast(pos, Vec2::NEG_Y, 16.0, |body| body.id != me && body.class == PhysicsClass::Solid);
+    ```
+    */
+    pub fn raycast(&self, origin: Vec2, dir: Vec2, max_dist: f32, filter: impl Fn(&PhysicsBody) -> bool) -> Option<RayHit> {
+        self.raycast_all(origin, dir, max_dist, filter).into_iter().next()
+    }
+
+    /**
+    Returns every body hit by a ray, sorted from nearest to farthest, see `raycast`.
+    Bodies with several parts, like chains and tilemaps, are only reported at their nearest hit.
+    */
+    pub fn raycast_all(&self, origin: Vec2, dir: Vec2, max_dist: f32, filter: impl Fn(&PhysicsBody) -> bool) -> SmallVec<[RayHit; 8]> {
+        let mut hits: SmallVec<[RayHit; 8]> = SmallVec::new();
+        let Some(dir) = dir.try_normalize() else {
+            println!("WARNING: raycast called with a zero direction");
+            return hits;
+        };
+
+        let mut add_hit = |body: &PhysicsBody, distance: f32, normal: Vec2| {
+            let hit = RayHit { id: body.id, point: origin + dir * distance, normal, distance, tile: body.tile };
+            match hits.iter_mut().find(|other| other.id == hit.id) {
+                Some(other) => if distance < other.distance { *other = hit },
+                None => hits.push(hit),
+            }
+        };
+
+        let mut query = SmallVec::new();
+        self.physics.query_ray(origin, dir, max_dist, &mut query, |body| !body.is_node() && filter(body));
+        for body in query {
+            let Some((distance, normal)) = body.get_shape().and_then(|shape| shape.raycast(origin, dir)) else { continue };
+            if distance <= max_dist {
+                add_hit(body, distance, normal);
+            }
+        }
+
+        let mut tiles = SmallVec::new();
+        self.physics.raycast_tiles(origin, dir, max_dist, &filter, &mut tiles);
+        for (body, distance, normal) in &tiles {
+            add_hit(body, *distance, *normal);
+        }
+
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+}
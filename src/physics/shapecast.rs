@@ -0,0 +1,75 @@
+use glam::Vec2;
+use smallvec::SmallVec;
+
+use crate::{TypedID, physics::{ALL_LAYERS, PhysicsBody, TileHit}, shapes::{Collider, Shape}, world::World};
+
+/// Where a swept collider first touched a body, see `World::shape_cast`
+#[derive(Clone, Copy)]
+pub struct ShapeHit {
+    pub id: TypedID,
+    /** fraction of `delta` travelled before the hit, from 0 to 1 */
+    pub toi: f32,
+    /** surface normal of the body that was hit, facing the collider */
+    pub normal: Vec2,
+    /** position of the collider at the time of impact */
+    pub pos: Vec2,
+    /** tile that was hit, when the body is a `TileMapCollider` */
+    pub tile: Option<TileHit>,
+}
+
+impl World {
+    /**
+    Moves a collider from `from` along `delta` without changing the world, and returns the
+    first body it would hit, ie. to predict where a projectile lands.
+
+    The collider is placed at `from` as with `Shape::set_pos`, so the top-left corner for an
+    AABB and the center for a circle. Only bodies for which `filter` returns true are tested.
+    Bodies the collider already overlaps at `from` are hit with a `toi` of 0.
+
+    ```ignore
+    let ball = Collider::circle(Vec2::ZERO, 4.0).unwrap();
+    if let Some(hit) = world.shape_cast(&ball, pos, velocity, |body| body.class == PhysicsClass::Solid) {
+        let landing = hit.pos;
+    }
+    ```
+    */
+    pub fn shape_cast(&self, collider: &Collider, from: Vec2, delta: Vec2, filter: impl Fn(&PhysicsBody) -> bool) -> Option<ShapeHit> {
+        let mut shape = *collider;
+        shape.set_pos(from);
+
+        // one query over the whole path, instead of one per step
+        let mut end = shape;
+        end.translate(delta);
+        let swept = shape.bounds().union(end.bounds());
+
+        let mut tiles = SmallVec::new();
+        self.physics.query_tiles(&swept, ALL_LAYERS, &mut tiles);
+        tiles.retain(|body| filter(body));
+        let mut query = SmallVec::new();
+        self.physics.query_filtered(&swept, &mut query, |body| !body.is_node() && filter(body));
+        query.extend(tiles.iter());
+
+        let length = delta.length();
+        let dir = delta.normalize_or_zero();
+        let mut first: Option<ShapeHit> = None;
+
+        for body in query {
+            let Some(other) = body.get_shape() else { continue };
+
+            let (toi, normal) = if let Some(contact) = shape.contact(other) {
+                (0.0, contact.normal)
+            } else {
+                if length == 0.0 { continue }
+                let Some((distance, normal)) = shape.sweep(other, dir) else { continue };
+                if distance > length { continue }
+                (distance / length, normal)
+            };
+
+            if first.is_none_or(|first| toi < first.toi) {
+                first = Some(ShapeHit { id: body.id, toi, normal, pos: from + delta * toi, tile: body.tile });
+            }
+        }
+
+        first
+    }
+}
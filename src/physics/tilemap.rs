@@ -0,0 +1,330 @@
+use glam::{IVec2, Vec2, vec2};
+use smallvec::SmallVec;
+
+use crate::{ID, TypedID, physics::{PhysicsBody, PhysicsClass}, shapes::{AABB, Collider, Polygon, solve}, world::World};
+
+/** tile blocks movement like a `Solid`. tiles without it act like a `Zone` */
+pub const TILE_SOLID: u8 = 1;
+/** tile is a one-way platform, solid only from above */
+pub const TILE_ONE_WAY: u8 = 1 << 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileShape {
+    Full,
+    /**
+    Ramp rising from the left edge to the right edge of the tile. Heights are a fraction of
+    the tile height, so `{ left: 0.0, right: 0.5 }` is the lower half of a 1:2 ramp.
+    */
+    Slope { left: f32, right: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    /** `TILE_SOLID`, `TILE_ONE_WAY`... */
+    pub flags: u8,
+    pub shape: TileShape,
+    /** free for game use, ie. to tell spikes from ice */
+    pub id: u16,
+}
+
+impl Tile {
+    pub fn solid() -> Self {
+        Self { flags: TILE_SOLID, shape: TileShape::Full, id: 0 }
+    }
+
+    pub fn one_way() -> Self {
+        Self { flags: TILE_SOLID | TILE_ONE_WAY, shape: TileShape::Full, id: 0 }
+    }
+
+    pub fn slope(left: f32, right: f32) -> Self {
+        Self { flags: TILE_SOLID, shape: TileShape::Slope { left, right }, id: 0 }
+    }
+
+    /// Non-solid tile, reported through collision hooks without blocking movement
+    pub fn zone() -> Self {
+        Self { flags: 0, shape: TileShape::Full, id: 0 }
+    }
+
+    pub fn with_id(mut self, id: u16) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn is_solid(&self) -> bool {
+        self.flags & TILE_SOLID != 0
+    }
+
+    pub fn is_one_way(&self) -> bool {
+        self.flags & TILE_ONE_WAY != 0
+    }
+}
+
+/// A tile and the grid cell it is in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileHit {
+    pub cell: IVec2,
+    pub tile: Tile,
+}
+
+/**
+Grid of tiles, answering collision queries by indexing cells directly instead of keeping
+a body per tile in the broad phase. Attach it to an actor with `World::set_tilemap`.
+
+Cell (0, 0) starts at `origin` and cells grow towards positive x and y.
+*/
+#[derive(Debug, Clone)]
+pub struct TileMapCollider {
+    origin: Vec2,
+    tile_size: Vec2,
+    width: u32,
+    height: u32,
+    tiles: Vec<Option<Tile>>,
+}
+
+impl TileMapCollider {
+    pub fn new(origin: Vec2, tile_size: Vec2, width: u32, height: u32) -> Self {
+        Self {
+            origin,
+            tile_size,
+            width,
+            height,
+            tiles: vec![None; (width * height) as usize],
+        }
+    }
+
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    pub fn tile_size(&self) -> Vec2 {
+        self.tile_size
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        if cell.x < 0 || cell.y < 0 || cell.x >= self.width as i32 || cell.y >= self.height as i32 {
+            return None;
+        }
+        Some((cell.y as u32 * self.width + cell.x as u32) as usize)
+    }
+
+    pub fn get(&self, cell: IVec2) -> Option<Tile> {
+        self.tiles[self.index(cell)?]
+    }
+
+    /// Changes a tile. Takes effect on the next collision query, there is nothing to rebuild.
+    pub fn set(&mut self, cell: IVec2, tile: Option<Tile>) {
+        let Some(index) = self.index(cell) else {
+            println!("WARNING: tile {:?} is outside of the {}x{} tilemap", cell, self.width, self.height);
+            return;
+        };
+        self.tiles[index] = tile;
+    }
+
+    /// Cell containing the given point. It may be outside of the map.
+    pub fn cell_at(&self, point: Vec2) -> IVec2 {
+        ((point - self.origin) / self.tile_size).floor().as_ivec2()
+    }
+
+    pub fn cell_bounds(&self, cell: IVec2) -> AABB {
+        AABB::from_pos_size(self.origin + cell.as_vec2() * self.tile_size, self.tile_size)
+    }
+
+    /// Tile containing the given point, ie. `contact.point` in `Actor::on_contact`
+    pub fn tile_at(&self, point: Vec2) -> Option<TileHit> {
+        let cell = self.cell_at(point);
+        self.get(cell).map(|tile| TileHit { cell, tile })
+    }
+
+    /// Collider covering a tile, in world space. Slopes with no height on either side have none
+    pub fn tile_collider(&self, cell: IVec2, tile: &Tile) -> Option<Collider> {
+        let bounds = self.cell_bounds(cell);
+        match tile.shape {
+            TileShape::Full => Some(Collider::AABB(bounds)),
+            TileShape::Slope { left, right } if left <= 0.0 && right <= 0.0 => None,
+            TileShape::Slope { left, right } => {
+                let size = self.tile_size;
+                // skip corners with no height, so a full ramp is a triangle
+                let mut points: SmallVec<[Vec2; 4]> = SmallVec::new();
+                points.push(Vec2::ZERO);
+                points.push(vec2(size.x, 0.0));
+                if right > 0.0 { points.push(vec2(size.x, size.y * right)); }
+                if left > 0.0 { points.push(vec2(0.0, size.y * left)); }
+
+                Polygon::new(bounds.min, &points).map(Collider::Polygon)
+            }
+        }
+    }
+
+    /// Builds a body for every tile overlapping `bounds`, owned by `owner`
+    pub(crate) fn bodies_in(&self, bounds: &AABB, owner: &PhysicsBody, out: &mut SmallVec<[PhysicsBody; 8]>) {
+        let min = self.cell_at(bounds.min).max(IVec2::ZERO);
+        let max = self.cell_at(bounds.max).min(IVec2::new(self.width as i32 - 1, self.height as i32 - 1));
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = IVec2::new(x, y);
+                let Some(tile) = self.get(cell) else { continue };
+                out.extend(self.tile_body(cell, tile, owner));
+            }
+        }
+    }
+
+    fn tile_body(&self, cell: IVec2, tile: Tile, owner: &PhysicsBody) -> Option<PhysicsBody> {
+        let class = if tile.is_solid() { owner.class } else { PhysicsClass::Zone };
+        let collider = self.tile_collider(cell, &tile)?;
+        let mut body = PhysicsBody::new(self.cell_bounds(cell).min, Some(collider), owner.id, class)
+            .with_layers(owner.layer, owner.mask);
+        body.one_way = if tile.is_one_way() { Some(Vec2::Y) } else { owner.one_way };
+        body.tile = Some(TileHit { cell, tile });
+        Some(body)
+    }
+
+    /**
+    Walks the cells along a ray in order, returning the body of the first tile it hits that
+    passes `filter`, with the distance and normal of the hit. `dir` must be normalized.
+    */
+    pub(crate) fn raycast(&self, origin: Vec2, dir: Vec2, max_dist: f32, owner: &PhysicsBody, filter: &impl Fn(&PhysicsBody) -> bool) -> Option<(PhysicsBody, f32, Vec2)> {
+        let map_bounds = AABB::from_pos_size(self.origin, self.tile_size * vec2(self.width as f32, self.height as f32));
+        let (start, _) = solve::ray_aabb(origin, dir, &map_bounds).filter(|(t, _)| *t <= max_dist)?;
+
+        let last = IVec2::new(self.width as i32 - 1, self.height as i32 - 1);
+        let mut cell = self.cell_at(origin + dir * start).clamp(IVec2::ZERO, last);
+
+        // distance to the next cell boundary on each axis, and between boundaries
+        let step = IVec2::new(sign(dir.x), sign(dir.y));
+        let bounds = self.cell_bounds(cell);
+        let boundary = vec2(
+            if dir.x > 0.0 { bounds.max.x } else { bounds.min.x },
+            if dir.y > 0.0 { bounds.max.y } else { bounds.min.y },
+        );
+        let mut t_next = vec2(
+            if dir.x != 0.0 { (boundary.x - origin.x) / dir.x } else { f32::INFINITY },
+            if dir.y != 0.0 { (boundary.y - origin.y) / dir.y } else { f32::INFINITY },
+        );
+        let t_delta = self.tile_size / dir.abs();
+
+        loop {
+            if let Some(body) = self.get(cell).and_then(|tile| self.tile_body(cell, tile, owner)) {
+                if filter(&body) {
+                    let hit = body.get_shape().and_then(|shape| shape.raycast(origin, dir));
+                    if let Some((distance, normal)) = hit.filter(|(t, _)| *t <= max_dist) {
+                        return Some((body, distance, normal));
+                    }
+                }
+            }
+
+            if t_next.x.min(t_next.y) > max_dist { return None }
+            if t_next.x < t_next.y {
+                cell.x += step.x;
+                t_next.x += t_delta.x;
+            } else {
+                cell.y += step.y;
+                t_next.y += t_delta.y;
+            }
+            if cell.x < 0 || cell.y < 0 || cell.x > last.x || cell.y > last.y { return None }
+        }
+    }
+}
+
+fn sign(value: f32) -> i32 {
+    if value > 0.0 { 1 } else if value < 0.0 { -1 } else { 0 }
+}
+
+/// A tilemap and a copy of the body of the actor that owns it, kept up to date by `set_collision_layers`
+pub(crate) struct TileMapEntry {
+    pub owner: PhysicsBody,
+    pub map: TileMapCollider,
+}
+
+impl World {
+    /**
+    Gives an actor a tilemap. Tiles collide with the class and collision layers of the
+    actor's body, and collisions with them are reported with the actor's id. Calling this
+    again replaces the previous tilemap. `TILE_ONE_WAY` tiles block from above, as if their
+    `PhysicsBody::one_way` was `Vec2::Y`.
+
+    Actors overlapping tiles get `Actor::on_tile_collision` with each tile, and
+    `MovementResults` has the solid tiles `move_and_slide` stopped against:
+
+    ```ignore
+    fn on_tile_collision(&mut self, id: &ID<Self>, tilemap: TypedID, tile: TileHit, world: &mut World) {
+        if tile.tile.id == SPIKES {
+            world.remove_actor(id);
+        }
+    }
+    ```
+    */
+    pub fn set_tilemap<T: 'static>(&mut self, id: &ID<T>, tilemap: TileMapCollider) {
+        let Some(owner) = self.physics.get_body(id).copied() else {
+            println!("WARNING: set_tilemap called for an actor without a physics body");
+            return;
+        };
+        self.physics.set_tilemap(owner, tilemap);
+    }
+
+    pub fn tilemap<T: 'static>(&self, id: &ID<T>) -> Option<&TileMapCollider> {
+        self.physics.get_tilemap(id.into_typed_id())
+    }
+
+    /// Tiles can be changed at any time, ie. for destructible terrain
+    pub fn tilemap_mut<T: 'static>(&mut self, id: &ID<T>) -> Option<&mut TileMapCollider> {
+        self.physics.get_tilemap_mut(id.into_typed_id())
+    }
+}
+
+impl crate::physics::Physics {
+    pub(crate) fn set_tilemap(&mut self, owner: PhysicsBody, map: TileMapCollider) {
+        self.remove_tilemap(owner.id);
+        self.tilemaps.push(TileMapEntry { owner, map });
+    }
+
+    /// Copies the class, layers and one-way direction of `owner` to its tilemap, after they changed
+    pub(crate) fn sync_tilemap(&mut self, owner: &PhysicsBody) {
+        if let Some(entry) = self.tilemaps.iter_mut().find(|entry| entry.owner.id == owner.id) {
+            entry.owner = *owner;
+        }
+    }
+
+    pub(crate) fn remove_tilemap(&mut self, id: TypedID) {
+        self.tilemaps.retain(|entry| entry.owner.id != id);
+    }
+
+    pub(crate) fn get_tilemap(&self, id: TypedID) -> Option<&TileMapCollider> {
+        self.tilemaps.iter().find(|entry| entry.owner.id == id).map(|entry| &entry.map)
+    }
+
+    pub(crate) fn get_tilemap_mut(&mut self, id: TypedID) -> Option<&mut TileMapCollider> {
+        self.tilemaps.iter_mut().find(|entry| entry.owner.id == id).map(|entry| &mut entry.map)
+    }
+
+    /// Builds bodies for the tiles overlapping `bounds` that `body` can collide with
+    pub(crate) fn query_tiles_against_body(&self, bounds: &AABB, out: &mut SmallVec<[PhysicsBody; 8]>, body: &PhysicsBody) {
+        for entry in &self.tilemaps {
+            if entry.owner.id == body.id || !body.collides_with_layers(&entry.owner) { continue }
+            entry.map.bodies_in(bounds, &entry.owner, out);
+        }
+    }
+
+    /// First tile hit by the ray on every tilemap, see `TileMapCollider::raycast`
+    pub(crate) fn raycast_tiles(&self, origin: Vec2, dir: Vec2, max_dist: f32, filter: &impl Fn(&PhysicsBody) -> bool, out: &mut SmallVec<[(PhysicsBody, f32, Vec2); 4]>) {
+        for entry in &self.tilemaps {
+            out.extend(entry.map.raycast(origin, dir, max_dist, &entry.owner, filter));
+        }
+    }
+
+    /// Builds bodies for the tiles overlapping `bounds`, on tilemaps whose layer is in `mask`
+    pub(crate) fn query_tiles(&self, bounds: &AABB, mask: u32, out: &mut SmallVec<[PhysicsBody; 8]>) {
+        for entry in &self.tilemaps {
+            if entry.owner.layer & mask == 0 { continue }
+            entry.map.bodies_in(bounds, &entry.owner, out);
+        }
+    }
+}
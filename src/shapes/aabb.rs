@@ -226,6 +226,10 @@ impl Shape for AABB {
         closest.distance_squared(other.pos) < other.radius * other.radius
     }
 
+    fn overlaps_capsule(&self, other: &Capsule) -> bool {
+        super::solve::overlaps_poly_capsule(self, other)
+    }
+
     fn edges(&self) -> Option<Vec<Edge>> {
         let (a, b, c, d) = (
             self.bottom_left(),
@@ -0,0 +1,98 @@
+use glam::*;
+use super::*;
+
+/**
+Capsule: a segment through `pos` grown by `radius`, like a stretched circle.
+
+With a `rotation` of 0 the segment is vertical, which suits characters since there are
+no corners to snag on. `pos` is the center of the capsule.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Capsule {
+    pub pos: Vec2,
+    /** half the distance between the centers of the two caps */
+    pub half_length: f32,
+    pub radius: f32,
+    /** counter-clockwise rotation in radians */
+    pub rotation: f32,
+}
+
+impl Capsule {
+    pub fn new(pos: Vec2, half_length: f32, radius: f32) -> Self {
+        Self { pos, half_length, radius, rotation: 0.0 }
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Direction from the center towards the top cap
+    pub fn axis(&self) -> Vec2 {
+        Vec2::from_angle(self.rotation).rotate(Vec2::Y)
+    }
+
+    /// Centers of the two caps, in world space
+    pub fn segment(&self) -> [Vec2; 2] {
+        let offset = self.axis() * self.half_length;
+        [self.pos - offset, self.pos + offset]
+    }
+
+    pub fn height(&self) -> f32 {
+        (self.half_length + self.radius) * 2.0
+    }
+}
+
+impl Shape for Capsule {
+    fn as_collision_shape(&self) -> Collider {
+        Collider::Capsule(*self)
+    }
+
+    fn centroid(&self) -> Vec2 {
+        self.pos
+    }
+
+    fn bounds(&self) -> AABB {
+        let [a, b] = self.segment();
+        AABB {
+            min: a.min(b) - Vec2::splat(self.radius),
+            max: a.max(b) + Vec2::splat(self.radius),
+        }
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn set_pos(&mut self, pos: Vec2) {
+        self.pos = pos;
+    }
+
+    fn overlaps_point(&self, point: Vec2) -> bool {
+        self.overlaps_circle(&Circle::new(point, 0.0))
+    }
+
+    fn overlaps_edge(&self, edge: Edge) -> bool {
+        super::solve::overlaps_capsule_edge(self, &edge)
+    }
+
+    fn overlaps_polygon(&self, other: &impl Shape) -> bool {
+        super::solve::overlaps_poly_capsule(other, self)
+    }
+
+    fn overlaps_circle(&self, other: &Circle) -> bool {
+        super::solve::overlaps_capsule_circle(self, other)
+    }
+
+    fn overlaps_capsule(&self, other: &Capsule) -> bool {
+        super::solve::overlaps_capsule_capsule(self, other)
+    }
+
+    fn edges(&self) -> Option<Vec<Edge>> {
+        None
+    }
+
+    fn vertices(&self) -> Option<Vec<Vec2>> {
+        None
+    }
+}
@@ -19,9 +19,7 @@ impl Circle {
     pub fn diameter(&self) -> f32 { self.radius * 2.0}
 
     pub fn overlaps_aabb(&self, other: &AABB) -> bool {
-        let d = self.radius + other.max.x - other.min.x;
-        let e = self.radius + other.max.y - other.min.y;
-        d * d + e * e <= self.radius * self.radius
+        other.overlaps_circle(self)
     }
 }
 
@@ -65,6 +63,10 @@ impl Shape for Circle {
         self.pos.distance(other.pos) < self.radius + other.radius
     }
 
+    fn overlaps_capsule(&self, other: &Capsule) -> bool {
+        super::solve::overlaps_capsule_circle(other, self)
+    }
+
     fn edges(&self) -> Option<Vec<Edge>> {
         None
     }
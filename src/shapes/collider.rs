@@ -1,10 +1,16 @@
 use glam::Vec2;
-use crate::shapes::{AABB, Circle, Edge, Shape};
+use smallvec::{smallvec, SmallVec};
+use crate::shapes::{AABB, Capsule, Circle, Contact, Edge, OBB, Polygon, Shape, solve};
 
 #[derive(Debug, Clone, Copy)]
 pub enum Collider {
     AABB(AABB),
-    CIRCLE(Circle)
+    CIRCLE(Circle),
+    Polygon(Polygon),
+    Capsule(Capsule),
+    OBB(OBB),
+    /** one edge of a static chain, see `World::set_chain` */
+    Chain(Edge),
 }
 
 impl Collider {
@@ -12,16 +18,71 @@ impl Collider {
         match self {
             Collider::AABB(shape) => shape.bounds(),
             Collider::CIRCLE(shape) => shape.bounds(),
+            Collider::Polygon(shape) => shape.bounds(),
+            Collider::Capsule(shape) => shape.bounds(),
+            Collider::OBB(shape) => shape.bounds(),
+            Collider::Chain(shape) => shape.bounds(),
         }
     }
 
     pub fn overlaps(&self, other: &Collider) -> bool {
         match (self, other) {
+            // fast path for the most common pair
             (Collider::AABB(a), Collider::AABB(b)) => a.overlaps_aabb(b),
-            (Collider::CIRCLE(a), Collider::AABB(b)) => a.overlaps_aabb(b),
-            (Collider::CIRCLE(a), Collider::CIRCLE(b)) => a.overlaps_circle(b),
-            (Collider::AABB(a), Collider::CIRCLE(b)) => a.overlaps_circle(b),
-            //_ => false
+            _ => Shape::overlaps(self, other),
+        }
+    }
+
+    /**
+    Returns the contact between the two colliders if they overlap.
+    The normal points from `other` towards `self`, see `Contact`.
+    */
+    pub fn contact(&self, other: &Collider) -> Option<Contact> {
+        match (self, other) {
+            (Collider::AABB(a), Collider::AABB(b)) => solve::contact_aabb_aabb(a, b),
+            (Collider::AABB(a), Collider::CIRCLE(b)) => solve::contact_aabb_circle(a, b),
+            (Collider::CIRCLE(a), Collider::CIRCLE(b)) => solve::contact_circle_circle(a, b),
+            (Collider::Capsule(a), Collider::CIRCLE(b)) => solve::contact_capsule_circle(a, b),
+            (Collider::Capsule(a), Collider::Capsule(b)) => solve::contact_capsule_capsule(a, b),
+            (Collider::CIRCLE(a), Collider::Capsule(b)) => solve::contact_capsule_circle(b, a).map(|c| c.flipped()),
+            // everything else is a polygon against a circle, a capsule or another polygon
+            (_, Collider::CIRCLE(b)) => solve::contact_poly_circle(self, b),
+            (_, Collider::Capsule(b)) => solve::contact_poly_capsule(self, b),
+            (Collider::CIRCLE(_) | Collider::Capsule(_), _) => other.contact(self).map(|c| c.flipped()),
+            _ => solve::contact_poly_poly(self, other),
+        }
+    }
+
+    /**
+    Casts a ray against the collider, returning the distance along the ray to the first hit
+    and the surface normal there. `dir` must be normalized, see `solve::ray_aabb`.
+    */
+    pub fn raycast(&self, origin: Vec2, dir: Vec2) -> Option<(f32, Vec2)> {
+        match self {
+            Collider::AABB(a) => solve::ray_aabb(origin, dir, a),
+            Collider::CIRCLE(c) => solve::ray_circle(origin, dir, c),
+            Collider::Capsule(c) => solve::ray_capsule(origin, dir, c),
+            Collider::Chain(e) => solve::ray_edge(origin, dir, e),
+            Collider::Polygon(_) | Collider::OBB(_) => solve::ray_convex(origin, dir, &self.vertices()?),
+        }
+    }
+
+    /**
+    Sweeps the collider along `dir` towards `other`, returning the distance it travels before
+    touching it and the normal of `other` at the contact. `dir` must be normalized.
+    */
+    pub fn sweep(&self, other: &Collider, dir: Vec2) -> Option<(f32, Vec2)> {
+        let (a, a_radius) = self.core();
+        let (b, b_radius) = other.core();
+        solve::sweep_rounded(&a, a_radius, &b, b_radius, dir)
+    }
+
+    /// Convex core of the collider and the radius it is grown by, see `solve::ray_rounded`
+    fn core(&self) -> (SmallVec<[Vec2; 8]>, f32) {
+        match self {
+            Collider::CIRCLE(c) => (smallvec![c.pos], c.radius),
+            Collider::Capsule(c) => (SmallVec::from_slice(&c.segment()), c.radius),
+            _ => (self.vertices().map(SmallVec::from_vec).unwrap_or_default(), 0.0),
         }
     }
 
@@ -32,6 +93,21 @@ impl Collider {
     pub fn circle(pos: Vec2, radius: f32) -> Option<Self> {
         Some(Collider::CIRCLE(Circle::new(pos, radius)))
     }
+
+    /// Convex polygon from vertices relative to `pos`, see `Polygon::new`
+    pub fn polygon(pos: Vec2, vertices: &[Vec2]) -> Option<Self> {
+        Some(Collider::Polygon(Polygon::new(pos, vertices)?))
+    }
+
+    /// Vertical capsule centered on `pos`
+    pub fn capsule(pos: Vec2, half_length: f32, radius: f32) -> Option<Self> {
+        Some(Collider::Capsule(Capsule::new(pos, half_length, radius)))
+    }
+
+    /// Box centered on `pos`, rotated by `rotation` radians
+    pub fn obb(pos: Vec2, size: Vec2, rotation: f32) -> Option<Self> {
+        Some(Collider::OBB(OBB::new(pos, size, rotation)))
+    }
 }
 
 impl Shape for Collider {
@@ -39,6 +115,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.centroid(),
             Collider::CIRCLE(c) => c.centroid(),
+            Collider::Polygon(p) => p.centroid(),
+            Collider::Capsule(c) => c.centroid(),
+            Collider::OBB(o) => o.centroid(),
+            Collider::Chain(e) => e.centroid(),
         }
     }
 
@@ -46,6 +126,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.edges(),
             Collider::CIRCLE(c) => c.edges(),
+            Collider::Polygon(p) => p.edges(),
+            Collider::Capsule(c) => c.edges(),
+            Collider::OBB(o) => o.edges(),
+            Collider::Chain(e) => e.edges(),
         }
     }
 
@@ -53,6 +137,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.vertices(),
             Collider::CIRCLE(c) => c.vertices(),
+            Collider::Polygon(p) => p.vertices(),
+            Collider::Capsule(c) => c.vertices(),
+            Collider::OBB(o) => o.vertices(),
+            Collider::Chain(e) => e.vertices(),
         }
     }
 
@@ -60,6 +148,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.translate(offset),
             Collider::CIRCLE(c) => c.translate(offset),
+            Collider::Polygon(p) => p.translate(offset),
+            Collider::Capsule(c) => c.translate(offset),
+            Collider::OBB(o) => o.translate(offset),
+            Collider::Chain(e) => e.translate(offset),
         }
     }
 
@@ -67,6 +159,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.set_pos(pos),
             Collider::CIRCLE(c) => c.set_pos(pos),
+            Collider::Polygon(p) => p.set_pos(pos),
+            Collider::Capsule(c) => c.set_pos(pos),
+            Collider::OBB(o) => o.set_pos(pos),
+            Collider::Chain(e) => e.set_pos(pos),
         }
     }
 
@@ -74,6 +170,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.bounds(),
             Collider::CIRCLE(c) => c.bounds(),
+            Collider::Polygon(p) => p.bounds(),
+            Collider::Capsule(c) => c.bounds(),
+            Collider::OBB(o) => o.bounds(),
+            Collider::Chain(e) => e.bounds(),
         }
     }
 
@@ -85,6 +185,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.overlaps_point(point),
             Collider::CIRCLE(c) => c.overlaps_point(point),
+            Collider::Polygon(p) => p.overlaps_point(point),
+            Collider::Capsule(c) => c.overlaps_point(point),
+            Collider::OBB(o) => o.overlaps_point(point),
+            Collider::Chain(e) => e.overlaps_point(point),
         }
     }
 
@@ -92,6 +196,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.overlaps_edge(edge),
             Collider::CIRCLE(c) => c.overlaps_edge(edge),
+            Collider::Polygon(p) => p.overlaps_edge(edge),
+            Collider::Capsule(c) => c.overlaps_edge(edge),
+            Collider::OBB(o) => o.overlaps_edge(edge),
+            Collider::Chain(e) => Shape::overlaps_edge(e, edge),
         }
     }
 
@@ -99,6 +207,10 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.overlaps_polygon(other),
             Collider::CIRCLE(c) => c.overlaps_polygon(other),
+            Collider::Polygon(p) => p.overlaps_polygon(other),
+            Collider::Capsule(c) => c.overlaps_polygon(other),
+            Collider::OBB(o) => o.overlaps_polygon(other),
+            Collider::Chain(e) => e.overlaps_polygon(other),
         }
     }
 
@@ -106,6 +218,21 @@ impl Shape for Collider {
         match self {
             Collider::AABB(a) => a.overlaps_circle(other),
             Collider::CIRCLE(c) => c.overlaps_circle(other),
+            Collider::Polygon(p) => p.overlaps_circle(other),
+            Collider::Capsule(c) => c.overlaps_circle(other),
+            Collider::OBB(o) => o.overlaps_circle(other),
+            Collider::Chain(e) => e.overlaps_circle(other),
+        }
+    }
+
+    fn overlaps_capsule(&self, other: &Capsule) -> bool {
+        match self {
+            Collider::AABB(a) => a.overlaps_capsule(other),
+            Collider::CIRCLE(c) => c.overlaps_capsule(other),
+            Collider::Polygon(p) => p.overlaps_capsule(other),
+            Collider::Capsule(c) => c.overlaps_capsule(other),
+            Collider::OBB(o) => o.overlaps_capsule(other),
+            Collider::Chain(e) => e.overlaps_capsule(other),
         }
     }
 }
\ No newline at end of file
@@ -3,22 +3,33 @@ use glam::*;
 
 mod aabb;
 mod circle;
-mod solve;
+mod polygon;
+mod capsule;
+mod obb;
+pub mod solve;
 mod aabb_i32;
 mod collider;
 
 pub use aabb::AABB;
 pub use circle::Circle;
+pub use polygon::{Polygon, MAX_POLYGON_VERTICES};
+pub use capsule::Capsule;
+pub use obb::OBB;
 pub use aabb_i32::AABBI32;
 pub use collider::Collider;
+pub use solve::Contact;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Edge {
-    a: Vec2,
-    b: Vec2
+    pub a: Vec2,
+    pub b: Vec2
 }
 
 impl Edge {
+    pub fn new(a: Vec2, b: Vec2) -> Self {
+        Self { a, b }
+    }
+
     pub fn perpendicular_dir(&self) -> Vec2 {
         Vec2::new(-(self.b.y - self.a.y), self.b.x - self.a.x).normalize()
     }
@@ -32,6 +43,61 @@ impl Edge {
     }
 }
 
+/**
+A single edge used as a collider, see `Collider::Chain`.
+Edges are two-sided and behave like a polygon with two vertices.
+*/
+impl Shape for Edge {
+    fn as_collision_shape(&self) -> Collider {
+        Collider::Chain(*self)
+    }
+
+    fn centroid(&self) -> Vec2 {
+        (self.a + self.b) / 2.0
+    }
+
+    fn bounds(&self) -> AABB {
+        AABB { min: self.a.min(self.b), max: self.a.max(self.b) }
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.a += offset;
+        self.b += offset;
+    }
+
+    fn set_pos(&mut self, pos: Vec2) {
+        self.translate(pos - self.a);
+    }
+
+    fn overlaps_point(&self, point: Vec2) -> bool {
+        Edge::overlaps_circle(self, &Circle::new(point, f32::EPSILON))
+    }
+
+    fn overlaps_edge(&self, edge: Edge) -> bool {
+        Edge::overlaps_edge(self, &edge)
+    }
+
+    fn overlaps_polygon(&self, other: &impl Shape) -> bool {
+        solve::overlaps_poly_poly(self, other)
+    }
+
+    fn overlaps_circle(&self, other: &Circle) -> bool {
+        Edge::overlaps_circle(self, other)
+    }
+
+    fn overlaps_capsule(&self, other: &Capsule) -> bool {
+        solve::overlaps_capsule_edge(other, self)
+    }
+
+    fn edges(&self) -> Option<Vec<Edge>> {
+        Some(vec![*self])
+    }
+
+    fn vertices(&self) -> Option<Vec<Vec2>> {
+        Some(vec![self.a, self.b])
+    }
+}
+
 pub trait Shape {
     fn centroid(&self) -> Vec2;
     fn edges(&self) -> Option<Vec<Edge>>;
@@ -48,6 +114,7 @@ pub trait Shape {
     fn overlaps_edge(&self, edge: Edge) -> bool;
     fn overlaps_polygon(&self, other: &impl Shape) -> bool;
     fn overlaps_circle(&self, other: &Circle) -> bool;
+    fn overlaps_capsule(&self, other: &Capsule) -> bool;
 
     fn point_within_bounds(&self, point: Vec2) -> bool {
         let bounds = self.bounds();
@@ -65,6 +132,10 @@ pub trait Shape {
         match other_shape {
             Collider::AABB(o) => self.overlaps_polygon(&o),
             Collider::CIRCLE(o) => self.overlaps_circle(&o),
+            Collider::Polygon(o) => self.overlaps_polygon(&o),
+            Collider::Capsule(o) => self.overlaps_capsule(&o),
+            Collider::OBB(o) => self.overlaps_polygon(&o),
+            Collider::Chain(o) => self.overlaps_polygon(&o),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,95 @@
+use glam::*;
+use super::*;
+
+/**
+Oriented bounding box: a rectangle rotated around its center `pos`.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct OBB {
+    pub pos: Vec2,
+    pub half_extents: Vec2,
+    /** counter-clockwise rotation in radians */
+    pub rotation: f32,
+}
+
+impl OBB {
+    pub fn new(pos: Vec2, size: Vec2, rotation: f32) -> Self {
+        Self { pos, half_extents: size / 2.0, rotation }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.half_extents * 2.0
+    }
+
+    /// Corners in world space, in counter-clockwise order
+    pub fn corners(&self) -> [Vec2; 4] {
+        let rotation = Vec2::from_angle(self.rotation);
+        let (x, y) = (self.half_extents.x, self.half_extents.y);
+        [vec2(-x, -y), vec2(x, -y), vec2(x, y), vec2(-x, y)]
+            .map(|corner| self.pos + rotation.rotate(corner))
+    }
+}
+
+impl Shape for OBB {
+    fn as_collision_shape(&self) -> Collider {
+        Collider::OBB(*self)
+    }
+
+    fn centroid(&self) -> Vec2 {
+        self.pos
+    }
+
+    fn bounds(&self) -> AABB {
+        let rotation = Vec2::from_angle(self.rotation);
+        // half size of the rotated box along each world axis
+        let extent = vec2(
+            rotation.x.abs() * self.half_extents.x + rotation.y.abs() * self.half_extents.y,
+            rotation.y.abs() * self.half_extents.x + rotation.x.abs() * self.half_extents.y,
+        );
+        AABB { min: self.pos - extent, max: self.pos + extent }
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn set_pos(&mut self, pos: Vec2) {
+        self.pos = pos;
+    }
+
+    fn overlaps_point(&self, point: Vec2) -> bool {
+        // rotate the point into the box's space
+        let local = Vec2::from_angle(-self.rotation).rotate(point - self.pos);
+        local.abs().cmple(self.half_extents).all()
+    }
+
+    fn overlaps_edge(&self, edge: Edge) -> bool {
+        super::solve::overlaps_poly_edge(self, &edge)
+    }
+
+    fn overlaps_polygon(&self, other: &impl Shape) -> bool {
+        super::solve::overlaps_poly_poly(self, other)
+    }
+
+    fn overlaps_circle(&self, other: &Circle) -> bool {
+        super::solve::overlaps_poly_circle(self, other)
+    }
+
+    fn overlaps_capsule(&self, other: &Capsule) -> bool {
+        super::solve::overlaps_poly_capsule(self, other)
+    }
+
+    fn edges(&self) -> Option<Vec<Edge>> {
+        let [a, b, c, d] = self.corners();
+        Some(vec![
+            Edge {a, b},
+            Edge {a: b, b: c},
+            Edge {a: c, b: d},
+            Edge {a: d, b: a},
+        ])
+    }
+
+    fn vertices(&self) -> Option<Vec<Vec2>> {
+        Some(self.corners().to_vec())
+    }
+}
@@ -0,0 +1,150 @@
+use glam::*;
+use super::*;
+
+/** maximum number of vertices of a `Polygon`, kept small so colliders stay `Copy` */
+pub const MAX_POLYGON_VERTICES: usize = 8;
+
+/**
+Convex polygon with up to `MAX_POLYGON_VERTICES` vertices, stored inline.
+
+Vertices are relative to `pos`, so moving the polygon only changes `pos`.
+They are always stored in counter-clockwise order.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Polygon {
+    pub pos: Vec2,
+    vertices: [Vec2; MAX_POLYGON_VERTICES],
+    len: u8,
+}
+
+impl Polygon {
+    /**
+    Creates a polygon at `pos` from vertices relative to it, in either winding order.
+
+    Returns `None` if there are fewer than 3 or more than `MAX_POLYGON_VERTICES` vertices,
+    or if the polygon is not convex.
+    */
+    pub fn new(pos: Vec2, points: &[Vec2]) -> Option<Self> {
+        if points.len() < 3 || points.len() > MAX_POLYGON_VERTICES {
+            println!("WARNING: polygons need between 3 and {} vertices, got {}", MAX_POLYGON_VERTICES, points.len());
+            return None;
+        }
+
+        let mut vertices = [Vec2::ZERO; MAX_POLYGON_VERTICES];
+        vertices[..points.len()].copy_from_slice(points);
+        let mut polygon = Self { pos, vertices, len: points.len() as u8 };
+
+        if polygon.signed_area() < 0.0 {
+            polygon.vertices[..points.len()].reverse();
+        }
+
+        if !polygon.is_convex() {
+            println!("WARNING: polygon {:?} is not convex", points);
+            return None;
+        }
+
+        Some(polygon)
+    }
+
+    /// Vertices relative to `pos`, in counter-clockwise order
+    pub fn local_vertices(&self) -> &[Vec2] {
+        &self.vertices[..self.len as usize]
+    }
+
+    /// Vertices in world space, in counter-clockwise order
+    pub fn world_vertices(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.local_vertices().iter().map(|v| self.pos + *v)
+    }
+
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+
+    fn signed_area(&self) -> f32 {
+        let vertices = self.local_vertices();
+        let mut area = 0.0;
+        for i in 0..vertices.len() {
+            area += vertices[i].perp_dot(vertices[(i + 1) % vertices.len()]);
+        }
+        area / 2.0
+    }
+
+    fn is_convex(&self) -> bool {
+        let vertices = self.local_vertices();
+        let n = vertices.len();
+        if self.signed_area() <= 0.0 { return false }
+
+        (0..n).all(|i| {
+            let (a, b, c) = (vertices[i], vertices[(i + 1) % n], vertices[(i + 2) % n]);
+            (b - a).perp_dot(c - b) >= 0.0
+        })
+    }
+}
+
+impl Shape for Polygon {
+    fn as_collision_shape(&self) -> Collider {
+        Collider::Polygon(*self)
+    }
+
+    fn centroid(&self) -> Vec2 {
+        let vertices = self.local_vertices();
+        let mut centroid = Vec2::ZERO;
+        for i in 0..vertices.len() {
+            let (a, b) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+            centroid += (a + b) * a.perp_dot(b);
+        }
+        self.pos + centroid / (6.0 * self.signed_area())
+    }
+
+    fn bounds(&self) -> AABB {
+        let (min, max) = self.world_vertices().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), v| (min.min(v), max.max(v))
+        );
+        AABB { min, max }
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn set_pos(&mut self, pos: Vec2) {
+        self.pos = pos;
+    }
+
+    fn overlaps_point(&self, point: Vec2) -> bool {
+        let vertices = self.local_vertices();
+        let point = point - self.pos;
+        (0..vertices.len()).all(|i| {
+            let (a, b) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+            (b - a).perp_dot(point - a) >= 0.0
+        })
+    }
+
+    fn overlaps_edge(&self, edge: Edge) -> bool {
+        super::solve::overlaps_poly_edge(self, &edge)
+    }
+
+    fn overlaps_polygon(&self, other: &impl Shape) -> bool {
+        super::solve::overlaps_poly_poly(self, other)
+    }
+
+    fn overlaps_circle(&self, other: &Circle) -> bool {
+        super::solve::overlaps_poly_circle(self, other)
+    }
+
+    fn overlaps_capsule(&self, other: &Capsule) -> bool {
+        super::solve::overlaps_poly_capsule(self, other)
+    }
+
+    fn edges(&self) -> Option<Vec<Edge>> {
+        let vertices: Vec<Vec2> = self.world_vertices().collect();
+        Some((0..vertices.len())
+            .map(|i| Edge { a: vertices[i], b: vertices[(i + 1) % vertices.len()] })
+            .collect())
+    }
+
+    fn vertices(&self) -> Option<Vec<Vec2>> {
+        Some(self.world_vertices().collect())
+    }
+}
@@ -1,4 +1,6 @@
-use super::{Circle, Edge, Shape};
+use glam::Vec2;
+use smallvec::SmallVec;
+use super::{AABB, Capsule, Circle, Collider, Edge, Shape};
 
 pub fn overlaps_edge_edge(a: &Edge, b: &Edge) -> bool {
     let (a0, b0) = (a.a, a.b);
@@ -47,55 +49,491 @@ pub fn overlaps_poly_edge(a: &impl Shape, b: &Edge) -> bool {
 }
 
 pub fn overlaps_poly_poly(a: &impl Shape, b: &impl Shape) -> bool {
-    let self_edges = if let Some(edges) = a.edges() {
-        edges
-    } else { return false; };
-
-    let self_verts = if let Some(verts) = a.vertices() {
-        verts
-    } else { return false; };
-
-    let other_verts = if let Some(verts) = b.vertices() {
-        verts
-    } else { return false; };
-
-    for edge in &self_edges {
-        let axis = edge.perpendicular_dir();
-
-        let (mut min_self, mut max_self) = (f32::INFINITY, f32::NEG_INFINITY);
-        for &vert in &self_verts {
-            let dot = axis.dot(vert);
-            if dot < min_self {
-                min_self = dot;
-            }
-            if dot > max_self {
-                max_self = dot;
-            }
+    let (Some(a_verts), Some(b_verts)) = (a.vertices(), b.vertices()) else {
+        // shapes without vertices, like circles, have their own exact tests
+        return a.as_collision_shape().overlaps(&b.as_collision_shape());
+    };
+
+    separating_axis(&a_verts, 0.0, &b_verts, 0.0).is_some()
+}
+
+pub fn overlaps_poly_circle(a: &impl Shape, b: &Circle) -> bool {
+    let Some(edges) = a.edges() else {
+        return a.as_collision_shape().overlaps(&Collider::CIRCLE(*b));
+    };
+
+    // circle fully inside the polygon
+    if a.overlaps_point(b.pos) { return true }
+
+    for edge in edges {
+        if edge.overlaps_circle(b) { return true }
+    }
+
+    false
+}
+
+pub fn overlaps_poly_capsule(a: &impl Shape, b: &Capsule) -> bool {
+    let Some(vertices) = a.vertices() else {
+        return a.as_collision_shape().overlaps(&Collider::Capsule(*b));
+    };
+    separating_axis(&vertices, 0.0, &b.segment(), b.radius).is_some()
+}
+
+pub fn overlaps_capsule_circle(a: &Capsule, b: &Circle) -> bool {
+    let [start, end] = a.segment();
+    closest_point_on_segment(start, end, b.pos).distance_squared(b.pos) < (a.radius + b.radius).powi(2)
+}
+
+pub fn overlaps_capsule_capsule(a: &Capsule, b: &Capsule) -> bool {
+    separating_axis(&a.segment(), a.radius, &b.segment(), b.radius).is_some()
+}
+
+pub fn overlaps_capsule_edge(a: &Capsule, b: &Edge) -> bool {
+    separating_axis(&a.segment(), a.radius, &[b.a, b.b], 0.0).is_some()
+}
+
+/// Projects every vertex onto `axis`, returning the (min, max) range
+fn project(vertices: &[Vec2], axis: Vec2) -> (f32, f32) {
+    vertices.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+        let dot = axis.dot(*v);
+        (min.min(dot), max.max(dot))
+    })
+}
+
+/// Unit normals of every edge of a convex polygon, the candidate separating axes
+fn edge_normals(vertices: &[Vec2]) -> impl Iterator<Item = Vec2> + '_ {
+    (0..vertices.len()).map(move |i| {
+        (vertices[(i + 1) % vertices.len()] - vertices[i]).perp().normalize_or_zero()
+    })
+}
+
+/**
+Separating axis test between two convex shapes, each given as a core (a polygon, a segment
+or a single point) grown by a radius. Polygons have a radius of 0, capsules are a segment
+and circles a point with a radius.
+
+Returns `None` if the shapes are separated (touching does not count as overlapping),
+otherwise the axis of least penetration, pointing from `b` towards `a`, and the depth along it.
+*/
+fn separating_axis(a: &[Vec2], a_radius: f32, b: &[Vec2], b_radius: f32) -> Option<(Vec2, f32)> {
+    let mut axes: SmallVec<[Vec2; 24]> = edge_normals(a).chain(edge_normals(b)).collect();
+    // rounded shapes also separate along the direction to the closest point of their core
+    if a_radius > 0.0 {
+        axes.extend(b.iter().map(|v| (*v - closest_point_on_core(a, *v)).normalize_or_zero()));
+    }
+    if b_radius > 0.0 {
+        axes.extend(a.iter().map(|v| (*v - closest_point_on_core(b, *v)).normalize_or_zero()));
+    }
+
+    let mut best: Option<(Vec2, f32)> = None;
+
+    for axis in axes {
+        if axis == Vec2::ZERO { continue } // degenerate edge
+
+        let (min_a, max_a) = project(a, axis);
+        let (min_a, max_a) = (min_a - a_radius, max_a + a_radius);
+        let (min_b, max_b) = project(b, axis);
+        let (min_b, max_b) = (min_b - b_radius, max_b + b_radius);
+        if min_a >= max_b || min_b >= max_a { return None }
+
+        // push `a` out on the side that needs the least movement
+        let (normal, depth) = if max_a - min_b < max_b - min_a {
+            (-axis, max_a - min_b)
+        } else {
+            (axis, max_b - min_a)
+        };
+
+        if best.is_none_or(|(_, best_depth)| depth < best_depth) {
+            best = Some((normal, depth));
         }
-        let (mut min_other, mut max_other) = (f32::INFINITY, f32::NEG_INFINITY);
-        for &vert in &other_verts {
-            let dot = axis.dot(vert);
-            if dot < min_other {
-                min_other = dot;
-            }
-            if dot > max_other {
-                max_other = dot;
-            }
+    }
+
+    best
+}
+
+fn closest_point_on_core(core: &[Vec2], point: Vec2) -> Vec2 {
+    match core {
+        [a, b] => closest_point_on_segment(*a, *b, point),
+        _ => core[0],
+    }
+}
+
+/// Inclusive point in convex polygon test, for either winding order
+fn convex_contains(vertices: &[Vec2], point: Vec2) -> bool {
+    let (mut positive, mut negative) = (false, false);
+    for i in 0..vertices.len() {
+        let (a, b) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+        let side = (b - a).perp_dot(point - a);
+        positive |= side > 0.0;
+        negative |= side < 0.0;
+    }
+    !(positive && negative)
+}
+
+fn closest_point_on_segment(a: Vec2, b: Vec2, point: Vec2) -> Vec2 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared == 0.0 { return a }
+    a + ab * ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0)
+}
+
+/**
+Contact between two overlapping shapes, from the point of view of the first shape (`a`).
+
+`normal` points from `b` towards `a`: moving `a` by `normal * depth` separates the shapes.
+`point` is a point in the overlapping area, in world space.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub normal: Vec2,
+    pub depth: f32,
+    pub point: Vec2,
+}
+
+impl Contact {
+    /// Returns the same contact from the point of view of the other shape
+    pub fn flipped(&self) -> Self {
+        Self { normal: -self.normal, depth: self.depth, point: self.point }
+    }
+}
+
+pub fn contact_aabb_aabb(a: &AABB, b: &AABB) -> Option<Contact> {
+    if !a.overlaps_aabb(b) { return None }
+
+    let overlap_min = a.min.max(b.min);
+    let overlap_max = a.max.min(b.max);
+    let overlap = overlap_max - overlap_min;
+    let point = (overlap_min + overlap_max) / 2.0;
+
+    let offset = a.center() - b.center();
+    // separate along the axis with the least penetration
+    if overlap.x < overlap.y {
+        let normal = Vec2::new(if offset.x < 0.0 { -1.0 } else { 1.0 }, 0.0);
+        Some(Contact { normal, depth: overlap.x, point })
+    } else {
+        let normal = Vec2::new(0.0, if offset.y < 0.0 { -1.0 } else { 1.0 });
+        Some(Contact { normal, depth: overlap.y, point })
+    }
+}
+
+pub fn contact_circle_circle(a: &Circle, b: &Circle) -> Option<Contact> {
+    let offset = a.pos - b.pos;
+    let distance = offset.length();
+    let radii = a.radius + b.radius;
+    if distance >= radii { return None }
+
+    // concentric circles have no meaningful direction, so pick one
+    let normal = if distance > f32::EPSILON { offset / distance } else { Vec2::Y };
+    let depth = radii - distance;
+    // halfway between the two surface points along the normal
+    let point = ((a.pos - normal * a.radius) + (b.pos + normal * b.radius)) / 2.0;
+
+    Some(Contact { normal, depth, point })
+}
+
+pub fn contact_aabb_circle(a: &AABB, b: &Circle) -> Option<Contact> {
+    let closest = b.pos.clamp(a.min, a.max);
+    let offset = closest - b.pos;
+    let distance_squared = offset.length_squared();
+
+    if distance_squared > 0.0 {
+        // circle center is outside of the box
+        if distance_squared >= b.radius * b.radius { return None }
+        let distance = distance_squared.sqrt();
+        return Some(Contact {
+            normal: offset / distance,
+            depth: b.radius - distance,
+            point: closest,
+        });
+    }
+
+    // circle center is inside the box: push the box so the circle leaves through the nearest face
+    let to_min = b.pos - a.min;
+    let to_max = a.max - b.pos;
+    let faces = [
+        (to_min.x, Vec2::X, Vec2::new(a.min.x, b.pos.y)),
+        (to_max.x, Vec2::NEG_X, Vec2::new(a.max.x, b.pos.y)),
+        (to_min.y, Vec2::Y, Vec2::new(b.pos.x, a.min.y)),
+        (to_max.y, Vec2::NEG_Y, Vec2::new(b.pos.x, a.max.y)),
+    ];
+    let (distance, normal, point) = faces.into_iter()
+        .min_by(|x, y| x.0.total_cmp(&y.0))
+        .unwrap();
+
+    Some(Contact { normal, depth: distance + b.radius, point })
+}
+
+pub fn contact_poly_poly(a: &impl Shape, b: &impl Shape) -> Option<Contact> {
+    let (a_verts, b_verts) = (a.vertices()?, b.vertices()?);
+    let (normal, depth) = separating_axis(&a_verts, 0.0, &b_verts, 0.0)?;
+
+    // the overlap of two convex shapes is convex, so the average of
+    // the vertices of each shape inside the other lies within it
+    let mut sum = Vec2::ZERO;
+    let mut count = 0;
+    for v in a_verts.iter().filter(|v| convex_contains(&b_verts, **v))
+        .chain(b_verts.iter().filter(|v| convex_contains(&a_verts, **v)))
+    {
+        sum += *v;
+        count += 1;
+    }
+
+    let point = if count > 0 {
+        sum / count as f32
+    } else {
+        // edges cross without any vertex inside: use the deepest vertex of `a`
+        let deepest = a_verts.iter().copied()
+            .min_by(|x, y| x.dot(normal).total_cmp(&y.dot(normal)))
+            .unwrap();
+        deepest + normal * depth / 2.0
+    };
+
+    Some(Contact { normal, depth, point })
+}
+
+pub fn contact_poly_circle(a: &impl Shape, b: &Circle) -> Option<Contact> {
+    let vertices = a.vertices()?;
+
+    let mut closest = vertices[0];
+    for i in 0..vertices.len() {
+        let point = closest_point_on_segment(vertices[i], vertices[(i + 1) % vertices.len()], b.pos);
+        if point.distance_squared(b.pos) < closest.distance_squared(b.pos) {
+            closest = point;
+        }
+    }
+
+    let offset = closest - b.pos;
+    let distance = offset.length();
+
+    if !convex_contains(&vertices, b.pos) {
+        if distance >= b.radius { return None }
+        return Some(Contact {
+            normal: offset / distance,
+            depth: b.radius - distance,
+            point: closest,
+        });
+    }
+
+    // circle center is inside the polygon: push the polygon so the circle leaves through the nearest edge
+    let normal = if distance > f32::EPSILON {
+        -offset / distance
+    } else {
+        // center lies on the boundary, so use the direction towards the polygon's center instead
+        (a.centroid() - b.pos).normalize_or(Vec2::Y)
+    };
+
+    Some(Contact { normal, depth: distance + b.radius, point: closest })
+}
+
+/// Contact between two rounded shapes, see `separating_axis`
+fn contact_rounded(a: &[Vec2], a_radius: f32, b: &[Vec2], b_radius: f32) -> Option<Contact> {
+    let (normal, depth) = separating_axis(a, a_radius, b, b_radius)?;
+
+    // halfway between the deepest point of one shape and the surface of the other. a rounded
+    // shape has a single deepest point, while a polygon may have a whole face at the same depth
+    let point = if a_radius > 0.0 || b_radius == 0.0 {
+        let deepest = a.iter().copied()
+            .min_by(|x, y| x.dot(normal).total_cmp(&y.dot(normal)))
+            .unwrap() - normal * a_radius;
+        deepest + normal * depth / 2.0
+    } else {
+        let deepest = b.iter().copied()
+            .max_by(|x, y| x.dot(normal).total_cmp(&y.dot(normal)))
+            .unwrap() + normal * b_radius;
+        deepest - normal * depth / 2.0
+    };
+
+    Some(Contact { normal, depth, point })
+}
+
+pub fn contact_poly_capsule(a: &impl Shape, b: &Capsule) -> Option<Contact> {
+    contact_rounded(&a.vertices()?, 0.0, &b.segment(), b.radius)
+}
+
+pub fn contact_capsule_circle(a: &Capsule, b: &Circle) -> Option<Contact> {
+    contact_rounded(&a.segment(), a.radius, &[b.pos], b.radius)
+}
+
+pub fn contact_capsule_capsule(a: &Capsule, b: &Capsule) -> Option<Contact> {
+    contact_rounded(&a.segment(), a.radius, &b.segment(), b.radius)
+}
+
+/**
+Casts a ray against an AABB, returning the distance along the ray to where it enters the
+box and the normal of the face it enters through.
+
+`dir` must be normalized. Like every `ray_*` function, a ray starting inside the shape hits
+at a distance of 0, with a normal facing back along the ray.
+*/
+pub fn ray_aabb(origin: Vec2, dir: Vec2, a: &AABB) -> Option<(f32, Vec2)> {
+    let (mut t_enter, mut t_exit) = (f32::NEG_INFINITY, f32::INFINITY);
+    let mut normal = Vec2::ZERO;
+
+    for axis in 0..2 {
+        let (o, d) = (origin[axis], dir[axis]);
+        let (min, max) = (a.min[axis], a.max[axis]);
+        if d == 0.0 {
+            // parallel to this slab, so it has to start inside it
+            if o < min || o > max { return None }
+            continue;
         }
-        if min_self > max_other || min_other > max_self {
-            return false;
+
+        let (mut near, mut far, mut side) = ((min - o) / d, (max - o) / d, -1.0);
+        if near > far {
+            std::mem::swap(&mut near, &mut far);
+            side = 1.0;
         }
+        if near > t_enter {
+            t_enter = near;
+            normal = Vec2::ZERO;
+            normal[axis] = side;
+        }
+        t_exit = t_exit.min(far);
     }
 
-    true
+    if t_enter > t_exit || t_exit < 0.0 { return None }
+    if t_enter < 0.0 { return Some((0.0, -dir)) }
+    Some((t_enter, normal))
 }
 
-pub fn overlaps_poly_circle(a: &impl Shape, b: &Circle) -> bool {
-    if let Some(edges) = a.edges() {
-        for edge in edges {
-            if edge.overlaps_circle(b) { return true }
+pub fn ray_circle(origin: Vec2, dir: Vec2, a: &Circle) -> Option<(f32, Vec2)> {
+    let offset = origin - a.pos;
+    let c = offset.length_squared() - a.radius * a.radius;
+    if c < 0.0 { return Some((0.0, -dir)) }
+
+    let b = offset.dot(dir);
+    if b > 0.0 { return None } // pointing away
+
+    let discriminant = b * b - c;
+    if discriminant < 0.0 { return None }
+
+    let t = -b - discriminant.sqrt();
+    Some((t, (origin + dir * t - a.pos).normalize_or(-dir)))
+}
+
+/// Edges are two-sided, the normal faces the side the ray comes from
+pub fn ray_edge(origin: Vec2, dir: Vec2, a: &Edge) -> Option<(f32, Vec2)> {
+    let ab = a.b - a.a;
+    let denominator = dir.perp_dot(ab);
+    if denominator == 0.0 { return None } // parallel, grazing an edge is not a hit
+
+    let to_edge = a.a - origin;
+    let t = to_edge.perp_dot(ab) / denominator;
+    let u = to_edge.perp_dot(dir) / denominator;
+    if t < 0.0 || !(0.0..=1.0).contains(&u) { return None }
+
+    let normal = ab.perp().normalize();
+    Some((t, if normal.dot(dir) > 0.0 { -normal } else { normal }))
+}
+
+/// Ray against a convex polygon given by its world space vertices, in either winding order
+pub fn ray_convex(origin: Vec2, dir: Vec2, vertices: &[Vec2]) -> Option<(f32, Vec2)> {
+    let center = vertices.iter().copied().sum::<Vec2>() / vertices.len() as f32;
+    let (mut t_enter, mut t_exit) = (f32::NEG_INFINITY, f32::INFINITY);
+    let mut normal = Vec2::ZERO;
+
+    for (i, a) in vertices.iter().enumerate() {
+        let mut outward = (vertices[(i + 1) % vertices.len()] - *a).perp();
+        if outward.dot(*a - center) < 0.0 { outward = -outward }
+
+        // positive while the origin is behind this edge
+        let distance = outward.dot(*a - origin);
+        let speed = outward.dot(dir);
+        if speed == 0.0 {
+            if distance < 0.0 { return None }
+            continue;
+        }
+
+        let t = distance / speed;
+        if speed < 0.0 {
+            if t > t_enter {
+                t_enter = t;
+                normal = outward;
+            }
+        } else {
+            t_exit = t_exit.min(t);
         }
     }
-    
-    false
-}
\ No newline at end of file
+
+    if t_enter > t_exit || t_exit < 0.0 { return None }
+    if t_enter < 0.0 { return Some((0.0, -dir)) }
+    Some((t_enter, normal.normalize()))
+}
+
+pub fn ray_capsule(origin: Vec2, dir: Vec2, a: &Capsule) -> Option<(f32, Vec2)> {
+    ray_rounded(origin, dir, &a.segment(), a.radius)
+}
+
+/**
+Ray against a convex core (a polygon, a segment or a single point) grown by `radius`,
+like the shapes in `separating_axis`.
+*/
+pub fn ray_rounded(origin: Vec2, dir: Vec2, core: &[Vec2], radius: f32) -> Option<(f32, Vec2)> {
+    if radius <= 0.0 {
+        return match core {
+            [] | [_] => None,
+            [a, b] => ray_edge(origin, dir, &Edge::new(*a, *b)),
+            _ => ray_convex(origin, dir, core),
+        };
+    }
+
+    // the rounded shape is the union of a circle on every vertex, a rectangle along every
+    // edge and the core itself, so the first hit is the nearest hit on any of them
+    let mut hits: SmallVec<[(f32, Vec2); 16]> = SmallVec::new();
+    for vertex in core {
+        hits.extend(ray_circle(origin, dir, &Circle::new(*vertex, radius)));
+    }
+
+    let edge_count = if core.len() == 2 { 1 } else { core.len() };
+    for i in 0..edge_count {
+        let (a, b) = (core[i], core[(i + 1) % core.len()]);
+        let side = (b - a).perp().normalize_or_zero() * radius;
+        if side == Vec2::ZERO { continue }
+        hits.extend(ray_convex(origin, dir, &[a + side, b + side, b - side, a - side]));
+    }
+
+    if core.len() >= 3 {
+        hits.extend(ray_convex(origin, dir, core));
+    }
+
+    hits.into_iter().min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/**
+Sweeps shape `a` along `dir` towards `b`, both given as a core and a radius like in
+`separating_axis`. Returns the distance `a` travels before touching `b`, and the normal of
+`b` at the contact, pointing towards `a`. `dir` must be normalized.
+
+`a` hits `b` once it has moved into their Minkowski difference, so this is a single ray
+against the rounded hull of every difference of their vertices.
+*/
+pub fn sweep_rounded(a: &[Vec2], a_radius: f32, b: &[Vec2], b_radius: f32, dir: Vec2) -> Option<(f32, Vec2)> {
+    let points = b.iter().flat_map(|b| a.iter().map(move |a| *b - *a)).collect();
+    ray_rounded(Vec2::ZERO, dir, &convex_hull(points), a_radius + b_radius)
+}
+
+/// Counter-clockwise convex hull, using Andrew's monotone chain
+fn convex_hull(mut points: SmallVec<[Vec2; 64]>) -> SmallVec<[Vec2; 16]> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 { return points.into_iter().collect() }
+
+    let turns_left = |hull: &[Vec2], point: Vec2| {
+        let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+        (b - a).perp_dot(point - a) > 0.0
+    };
+
+    let mut hull: SmallVec<[Vec2; 16]> = SmallVec::new();
+    for point in points.iter() {
+        while hull.len() >= 2 && !turns_left(&hull, *point) { hull.pop(); }
+        hull.push(*point);
+    }
+    let lower = hull.len() + 1;
+    for point in points.iter().rev().skip(1) {
+        while hull.len() >= lower && !turns_left(&hull, *point) { hull.pop(); }
+        hull.push(*point);
+    }
+    hull.pop(); // back at the first point
+
+    hull
+}
@@ -0,0 +1,70 @@
+use crate::{Actor, World, entity::ID};
+
+// a deferred change to the world
+pub(crate) type Command = Box<dyn FnOnce(&mut World)>;
+
+/**
+A buffer of deferred structural changes to the world (spawning and removing actors).
+
+Commands are not applied immediately; `World::update_systems` applies them before the first
+system, after every system has run, and once more before the physics tree is rebuilt at the
+end of the frame. This keeps actor storage stable while actors are being iterated, and while
+their hooks hold on to them.
+*/
+pub struct Commands {
+    queue: Vec<Command>,
+}
+
+impl Default for Commands {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Self {
+            queue: Vec::with_capacity(64),
+        }
+    }
+
+    /**
+    Queues an actor to be added to the world the next time commands are applied.
+
+    Use `World::add_actor` instead if you need the actor's ID right away.
+    */
+    pub fn spawn<T: Actor<P> + 'static, P: 'static>(&mut self, actor: T) {
+        self.add(move |world| {
+            let id = world.reserve_actor::<T, P>();
+            world.spawn_now(id, actor);
+        });
+    }
+
+    /**
+    Queues an actor to be removed from the world the next time commands are applied.
+
+    Removing the same actor more than once is harmless; `on_remove` only runs the first time.
+    */
+    pub fn despawn<T: Actor<P> + 'static, P: 'static>(&mut self, id: ID<T>) {
+        self.add(move |world| {
+            world.despawn_now(id);
+        });
+    }
+
+    /// Queues an arbitrary change to the world
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + 'static) {
+        self.queue.push(Box::new(command));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.queue)
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{Actor, ID, TypedID, World};
+
+pub(crate) struct DrawItem<P> {
+    key: f32,
+    id: TypedID,
+    draw: fn(&World, TypedID, &mut P),
+}
+
+type CollectFn<P> = fn(&World, &mut Vec<DrawItem<P>>);
+
+/// Draw systems for a single context type `P`
+pub(crate) struct DrawSystems<P> {
+    // (type name, collect function), sorted by name so ties are drawn in a stable order
+    collectors: Vec<(&'static str, CollectFn<P>)>,
+    // scratch buffer, reused every frame
+    queue: Vec<DrawItem<P>>,
+}
+
+impl<P: 'static> DrawSystems<P> {
+    pub fn new() -> Self {
+        Self {
+            collectors: Vec::with_capacity(32),
+            queue: Vec::with_capacity(1024),
+        }
+    }
+
+    pub fn register<T: Actor<P> + 'static>(&mut self) {
+        let name = std::any::type_name::<T>();
+        let index = self.collectors.partition_point(|(other, _)| *other < name);
+        self.collectors.insert(index, (name, collect_draws::<T, P>));
+    }
+}
+
+fn collect_draws<T: Actor<P> + 'static, P: 'static>(world: &World, out: &mut Vec<DrawItem<P>>) {
+    let Some(entry) = world.registry.get_entry::<T>() else { return };
+    for id in &entry.entities {
+        if let Some((id, actor)) = entry.arena.get(id.index) {
+            out.push(DrawItem {
+                key: actor.draw_order(id, world),
+                id: id.into_typed_id(),
+                draw: draw_actor::<T, P>,
+            });
+        }
+    }
+}
+
+fn draw_actor<T: Actor<P> + 'static, P: 'static>(world: &World, id: TypedID, ctx: &mut P) {
+    let id = ID::<T>::from_typed_id(id);
+    if let Some(actor) = world.get(&id) {
+        actor.draw(&id, world, ctx);
+    }
+}
+
+impl World {
+    pub(crate) fn register_draw_type<T: Actor<P> + 'static, P: 'static>(&mut self) {
+        if !self.draw_methods_any.contains::<DrawSystems<P>>() {
+            self.draw_methods_any.insert(DrawSystems::<P>::new());
+        }
+        self.draw_methods_any.get_mut::<DrawSystems<P>>().unwrap().register::<T>();
+    }
+
+    /**
+    Draws every actor of every type, calling `Actor::draw` in order of `Actor::draw_order`.
+
+    Unlike `update_systems`, this pass is read-only and ignores time scale, so paused actors
+    are still drawn. Call it once per rendered frame, after updating.
+    */
+    pub fn draw_systems<P: 'static>(&mut self, ctx: &mut P) {
+        let Some(systems) = self.draw_methods_any.get_mut::<DrawSystems<P>>() else {
+            println!("WARNING: no draw methods registered for the generic type {:?}", std::any::type_name::<P>());
+            return;
+        };
+
+        let collectors = systems.collectors.clone();
+        let mut queue = std::mem::take(&mut systems.queue);
+
+        for (_name, collect) in &collectors {
+            collect(self, &mut queue);
+        }
+
+        // stable sort, so equal keys keep type name and spawn order
+        queue.sort_by(|a, b| a.key.total_cmp(&b.key));
+
+        for item in queue.iter() {
+            (item.draw)(self, item.id, ctx);
+        }
+
+        queue.clear();
+        if let Some(systems) = self.draw_methods_any.get_mut::<DrawSystems<P>>() {
+            systems.queue = queue;
+        }
+    }
+}
+
@@ -1,5 +1,11 @@
 mod registry;
 mod world;
+mod commands;
+mod time;
+mod schedule;
+mod draw;
 
 pub use world::World;
-pub(crate) use registry::Registry;
+pub use commands::Commands;
+pub use time::Time;
+pub use schedule::{Phase, SystemConfig};
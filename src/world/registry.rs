@@ -1,11 +1,14 @@
-use rapidhash::{HashSetExt, RapidHashSet};
-use slotmap::SlotMap;
-use std::{any::TypeId, cell::OnceCell};
-use crate::{TypedID, World, entity::ID};
+use rapidhash::{HashSetExt, RapidHashMap, RapidHashSet};
+use slotmap::{SecondaryMap, SlotMap};
+use std::any::{Any, TypeId};
+use glam::Vec2;
+use crate::{Actor, TypedID, World, entity::{Deliver, ID, MovementResults}};
 
 
 pub(crate) struct RegistryEntry<T> {
-    pub arena: SlotMap<slotmap::DefaultKey,(ID<T>,T)>,
+    // hands out IDs, including those of actors that are queued to be added
+    pub keys: SlotMap<slotmap::DefaultKey,()>,
+    pub arena: SecondaryMap<slotmap::DefaultKey,(ID<T>,T)>,
     pub entities: Vec<ID<T>>,
 }
 
@@ -20,92 +23,164 @@ impl<T: 'static> RegistryEntry<T> {
     }
 }
 
+/**
+Type-erased operations for an actor type, recorded when the type is first registered.
+Lets the world remove or look up an actor from a `TypedID` without knowing its concrete type.
+*/
+#[derive(Clone, Copy)]
+pub(crate) struct ActorVTable {
+    pub type_name: &'static str,
+    pub remove: fn(&mut World, TypedID),
+    pub get: for<'a> fn(&'a World, TypedID) -> Option<&'a dyn Any>,
+    pub move_and_slide: fn(&mut World, TypedID, Vec2) -> MovementResults,
+    pub is_clinging: fn(&World, TypedID) -> bool,
+    pub squish: fn(&mut World, TypedID, TypedID),
+}
+
+impl ActorVTable {
+    pub fn of<T: Actor<P> + 'static, P: 'static>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            remove: |world, id| world.remove_actor(&ID::<T>::from_typed_id(id)),
+            get: |world, id| world.get(&ID::<T>::from_typed_id(id)).map(|actor| actor as &dyn Any),
+            move_and_slide: |world, id, delta| world.move_and_slide(ID::<T>::from_typed_id(id), &delta),
+            is_clinging: |world, id| {
+                // the actor whose hook is running is mutably borrowed, and can't be read here.
+                // other actors of its type can, since only that one actor is borrowed
+                if world.current_actor == Some(id) { return false }
+                let id = ID::<T>::from_typed_id(id);
+                world.registry.get(&id).is_some_and(|(id, actor)| actor.is_clinging(id, world))
+            },
+            squish: |world, id, solid| {
+                let id = ID::<T>::from_typed_id(id);
+                // safety: actors are only stored and removed by commands, and commands are never
+                // applied while queued actions run, so the actor stays in place during the hook
+                let Some(entry) = (unsafe { world.registry.get_mut_detached(&id) }) else { return };
+                let previous_actor = world.current_actor.replace(id.into_typed_id());
+                entry.1.on_squish(&id, solid, world);
+                world.current_actor = previous_actor;
+            },
+        }
+    }
+}
+
+/**
+Per-world storage for actors, one `RegistryEntry<T>` per actor type.
+
+Every `World` owns its own registry, so actors spawned in one world are never visible
+from another, and dropping the world drops all of its actors.
+*/
 pub(crate) struct Registry {
     pub types: RapidHashSet<TypeId>,
     pub recently_removed: RapidHashSet<TypedID>,
-}
+    pub vtables: RapidHashMap<TypeId, ActorVTable>,
+    // message handlers by actor type and message type, see `MessageHandlers`
+    pub messages: RapidHashMap<(TypeId, TypeId), Deliver>,
 
-static mut MAP: OnceCell<anymap::AnyMap> = OnceCell::new();
+    map: anymap::AnyMap,
+}
 
 impl Registry {
     pub fn new() -> Self {
-        unsafe {
-            MAP.get_or_init(|| {anymap::AnyMap::new()});
-        }
         Self {
             types: RapidHashSet::with_capacity(64),
             recently_removed: RapidHashSet::with_capacity(64),
+            vtables: RapidHashMap::default(),
+            messages: RapidHashMap::default(),
+            map: anymap::AnyMap::new(),
         }
     }
 
     #[inline(always)]
-    fn get_map() -> &'static mut anymap::AnyMap {
-        unsafe {MAP.get_mut().unwrap_unchecked()}
+    pub fn get_entry<T: 'static>(&self) -> Option<&RegistryEntry<T>> {
+        self.map.get::<RegistryEntry<T>>()
     }
 
     #[inline(always)]
-    pub fn get_entry<T: 'static>() -> &'static RegistryEntry<T> {
-        unsafe {MAP.get_mut().unwrap_unchecked()}.get::<RegistryEntry<T>>().unwrap()
+    pub fn get_entry_mut<T: 'static>(&mut self) -> Option<&mut RegistryEntry<T>> {
+        self.map.get_mut::<RegistryEntry<T>>()
     }
 
+    /**
+    Returns the entry for `T` without tying it to the borrow of the registry.
+
+    Entries are boxed inside the anymap, so their address is stable for as long as the
+    entry exists. This lets update systems hand `&mut World` to an actor while holding
+    a reference to that actor.
+
+    # Safety
+    The returned reference must not outlive the world that owns this registry.
+    */
     #[inline(always)]
-    pub fn get_entry_mut<T: 'static>() -> &'static mut RegistryEntry<T> {
-        unsafe {MAP.get_mut().unwrap_unchecked()}.get_mut::<RegistryEntry<T>>().unwrap()
+    pub unsafe fn get_entry_detached<'a, T: 'static>(&mut self) -> Option<&'a mut RegistryEntry<T>> {
+        let entry = self.map.get_mut::<RegistryEntry<T>>()? as *mut RegistryEntry<T>;
+        Some(unsafe { &mut *entry })
     }
 
-    pub fn create_entry<T: 'static>() -> &'static mut RegistryEntry<T> {
-        let &mut entry;
-        let map = Self::get_map();
-
-        if !map.contains::<RegistryEntry<T>>() {
-            let arena = SlotMap::<slotmap::DefaultKey,(ID<T>,T)>::with_capacity(1024);
+    pub fn create_entry<T: 'static>(&mut self) -> &mut RegistryEntry<T> {
+        if !self.map.contains::<RegistryEntry<T>>() {
+            let keys = SlotMap::<slotmap::DefaultKey,()>::with_capacity(1024);
+            let arena = SecondaryMap::<slotmap::DefaultKey,(ID<T>,T)>::with_capacity(1024);
             let entities = Vec::with_capacity(1024);
 
-            entry = RegistryEntry {
+            self.map.insert(RegistryEntry {
+                keys,
                 arena,
                 entities,
-            };
-            
-            map.insert(entry);
+            });
         }
 
-        map.get_mut::<RegistryEntry<T>>().unwrap()
+        self.map.get_mut::<RegistryEntry<T>>().unwrap()
     }
 
 
-    pub fn insert_actor<T: 'static>(entity: T) -> ID<T> {
-        let entry = Self::create_entry();
+    /**
+    Hands out the ID of an actor that is not stored yet, see `insert_actor`.
+
+    Only the key allocator grows here, never the arena, so references into the arena stay valid.
+    */
+    pub fn reserve_id<T: 'static>(&mut self) -> ID<T> {
+        ID::new(self.create_entry::<T>().keys.insert(()))
+    }
 
-        let idx = entry.arena.insert_with_key(|idx| {
-            (ID::new(idx), entity)
-        });
+    /// Stores an actor under an ID from `reserve_id`. May move every actor of the type in memory.
+    pub fn insert_actor<T: 'static>(&mut self, id: ID<T>, entity: T) {
+        let entry = self.create_entry();
 
-        let id = ID::new(idx);
+        entry.arena.insert(id.index, (id, entity));
         entry.entities.push(id);
-
-        id
     }
 
-    pub fn remove_actor<T: 'static>(id: &ID<T>) -> Option<T> {
-        let entry = Self::get_entry_mut::<T>();
+    pub fn remove_actor<T: 'static>(&mut self, id: &ID<T>) -> Option<T> {
+        let entry = self.get_entry_mut::<T>()?;
         let entity = entry.arena.remove(id.index)?;
+        entry.keys.remove(id.index);
         entry.entities.retain(|e| e.index != id.index);
         Some(entity.1)
     }
 
-    pub fn get<T: 'static>(id: &ID<T>) -> Option<&(ID<T>,T)> {
-        let entry = Self::get_map().get::<RegistryEntry<T>>().unwrap();
-        entry.arena.get(id.index)
+    pub fn get<T: 'static>(&self, id: &ID<T>) -> Option<&(ID<T>,T)> {
+        self.get_entry::<T>()?.arena.get(id.index)
     }
 
-    pub fn get_mut<T: 'static>(id: &ID<T>) -> Option<&mut (ID<T>,T)> {
-        let entry = Self::get_map().get_mut::<RegistryEntry<T>>().unwrap();
-        entry.arena.get_mut(id.index)
+    pub fn get_mut<T: 'static>(&mut self, id: &ID<T>) -> Option<&mut (ID<T>,T)> {
+        self.get_entry_mut::<T>()?.arena.get_mut(id.index)
+    }
+
+    /**
+    Same as `get_mut`, but the returned reference is not tied to the borrow of the registry.
+
+    # Safety
+    See `get_entry_detached`.
+    */
+    pub unsafe fn get_mut_detached<'a, T: 'static>(&mut self, id: &ID<T>) -> Option<&'a mut (ID<T>,T)> {
+        unsafe { self.get_entry_detached::<T>() }?.arena.get_mut(id.index)
     }
 
     pub fn iter_actors<T: 'static, P: 'static>(world: &mut World, ctx: &mut P, closure: impl Fn(&mut World, &mut P, &mut ID<T>, &mut T) + 'static) {
-        let entry = Self::get_map().get_mut::<RegistryEntry<T>>().unwrap();
-        entry.iter_actors(world, ctx, closure);
+        if let Some(entry) = unsafe { world.registry.get_entry_detached::<T>() } {
+            entry.iter_actors(world, ctx, closure);
+        }
     }
-    
-}
\ No newline at end of file
+
+}
@@ -0,0 +1,170 @@
+use std::any::TypeId;
+use rapidhash::RapidHashMap;
+use crate::World;
+
+/**
+Update phases, run in declaration order by `World::update_systems`.
+
+Actor types run in `Phase::Update` unless configured otherwise with `world.configure::<T>()`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Phase {
+    PreUpdate,
+    #[default]
+    Update,
+    PostUpdate,
+    Render,
+}
+
+pub(crate) struct SystemEntry<P> {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    pub run: fn(&mut World, &mut P),
+}
+
+impl<P> Clone for SystemEntry<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for SystemEntry<P> {}
+
+/// Update systems for a single context type `P`, kept sorted by the world's `Schedule`
+pub(crate) struct Systems<P> {
+    pub entries: Vec<SystemEntry<P>>,
+    // schedule version the entries were last sorted for
+    pub sorted_for: Option<u64>,
+}
+
+impl<P> Systems<P> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::with_capacity(32),
+            sorted_for: None,
+        }
+    }
+
+    pub fn push(&mut self, entry: SystemEntry<P>) {
+        self.entries.push(entry);
+        self.sorted_for = None;
+    }
+
+    pub fn sort(&mut self, schedule: &Schedule) {
+        if self.sorted_for == Some(schedule.version) { return }
+        self.entries = schedule.sort(&self.entries);
+        self.sorted_for = Some(schedule.version);
+    }
+}
+
+/**
+Ordering constraints between actor types.
+
+Types are sorted by phase first. Inside a phase, `before`/`after` constraints are respected,
+and anything left unordered is sorted by type name, so the order never depends on which
+type happened to be spawned first.
+*/
+#[derive(Default)]
+pub(crate) struct Schedule {
+    phases: RapidHashMap<TypeId, Phase>,
+    // (a, b): a runs before b
+    edges: Vec<(TypeId, TypeId)>,
+    version: u64,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn phase_of(&self, type_id: TypeId) -> Phase {
+        self.phases.get(&type_id).copied().unwrap_or_default()
+    }
+
+    fn set_phase(&mut self, type_id: TypeId, phase: Phase) {
+        self.phases.insert(type_id, phase);
+        self.version += 1;
+    }
+
+    fn add_edge(&mut self, before: TypeId, after: TypeId) {
+        if before == after || self.edges.contains(&(before, after)) { return }
+        self.edges.push((before, after));
+        self.version += 1;
+    }
+
+    fn sort<P>(&self, entries: &[SystemEntry<P>]) -> Vec<SystemEntry<P>> {
+        let mut sorted = Vec::with_capacity(entries.len());
+
+        let mut by_phase = entries.to_vec();
+        by_phase.sort_by(|a, b| {
+            self.phase_of(a.type_id).cmp(&self.phase_of(b.type_id))
+                .then(a.type_name.cmp(b.type_name))
+        });
+
+        // topological sort inside each phase, always picking the first
+        // ready entry by name so the result is deterministic
+        for phase_entries in by_phase.chunk_by(|a, b| self.phase_of(a.type_id) == self.phase_of(b.type_id)) {
+            let mut remaining = phase_entries.to_vec();
+
+            while !remaining.is_empty() {
+                let ready = remaining.iter().position(|entry| {
+                    !self.edges.iter().any(|(before, after)| {
+                        *after == entry.type_id && remaining.iter().any(|other| other.type_id == *before)
+                    })
+                });
+
+                match ready {
+                    Some(index) => sorted.push(remaining.remove(index)),
+                    None => {
+                        let names: Vec<_> = remaining.iter().map(|entry| entry.type_name).collect();
+                        panic!("system ordering contains a cycle between: {:?}", names);
+                    }
+                }
+            }
+        }
+
+        sorted
+    }
+}
+
+/**
+Builder returned by `World::configure`, used to declare when an actor type's update runs.
+
+```ignore
+world.configure::<Player>().before::<Enemy>();
+world.configure::<Camera>().in_phase(Phase::PostUpdate);
+```
+
+`before`/`after` only order types that are in the same phase.
+*/
+pub struct SystemConfig<'a> {
+    schedule: &'a mut Schedule,
+    type_id: TypeId,
+}
+
+impl<'a> SystemConfig<'a> {
+    pub fn in_phase(self, phase: Phase) -> Self {
+        self.schedule.set_phase(self.type_id, phase);
+        self
+    }
+
+    pub fn before<O: 'static>(self) -> Self {
+        self.schedule.add_edge(self.type_id, TypeId::of::<O>());
+        self
+    }
+
+    pub fn after<O: 'static>(self) -> Self {
+        self.schedule.add_edge(TypeId::of::<O>(), self.type_id);
+        self
+    }
+}
+
+impl World {
+    /// Configures the update order of an actor type. It does not need to be spawned yet.
+    pub fn configure<T: 'static>(&mut self) -> SystemConfig<'_> {
+        SystemConfig {
+            schedule: &mut self.schedule,
+            type_id: TypeId::of::<T>(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+/**
+Frame timing information, maintained by `World::update_systems` and read with `world.time()`.
+
+All scaled values use the time scale of the actor type that is currently updating,
+which is the world's time scale multiplied by the type's own scale.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Time {
+    pub(crate) delta: f32,
+    pub(crate) fixed_delta: f32,
+    pub(crate) elapsed: f64,
+    pub(crate) frame: u64,
+    pub(crate) scale: f32,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Time {
+    pub fn new() -> Self {
+        Self {
+            delta: 0.0,
+            fixed_delta: 1.0 / 60.0,
+            elapsed: 0.0,
+            frame: 0,
+            scale: 1.0,
+        }
+    }
+
+    /// Real time passed since the previous frame, in seconds, multiplied by the time scale
+    pub fn delta(&self) -> f32 {
+        self.delta * self.scale
+    }
+
+    /// Real time passed since the previous frame, in seconds
+    pub fn unscaled_delta(&self) -> f32 {
+        self.delta
+    }
+
+    /// Length of a fixed logic tick (see `World::step`), multiplied by the time scale
+    pub fn fixed_delta(&self) -> f32 {
+        self.fixed_delta * self.scale
+    }
+
+    /// Length of a fixed logic tick (see `World::step`)
+    pub fn unscaled_fixed_delta(&self) -> f32 {
+        self.fixed_delta
+    }
+
+    /// Total game time simulated so far, in seconds, using the world's time scale
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Number of times `update_systems` has run, starting at 0 for the first update
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.scale == 0.0
+    }
+}
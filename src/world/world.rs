@@ -1,28 +1,53 @@
-use std::any::TypeId;
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
 use std::time::{Duration, Instant};
 
 use anymap::AnyMap;
-use glam::{vec2};
+use rapidhash::RapidHashMap;
+use glam::{vec2, Vec2};
 
 use crate::TypedID;
-use crate::events::{EventBus, EventQueue};
+use crate::events::{Channels, EventBus, EventQueue, SubscriptionHandle};
 use crate::physics::{Physics};
 use crate::shapes::AABB;
-use crate::entity::{Actor, ID};
-use crate::world::registry::Registry;
+use crate::entity::{Actor, Handles, MessageHandlers, ID};
+use crate::world::registry::{ActorVTable, Registry};
+use crate::world::{Commands, Time};
+use crate::world::schedule::{Schedule, SystemEntry, Systems};
+// the largest frame time `step()` will try to catch up on
+const MAX_FRAME_TIME: f32 = 0.25;
+
 pub struct World {
     pub(crate) registry: Registry,
     pub logic_update: Duration,
+
+    // fixed timestep state, see `step()`
+    fixed_timestep: f32,
+    accumulator: f64,
+    // delta of the frame being stepped through, set by `step()` for the duration of its ticks
+    frame_delta: Option<f32>,
+
+    time: Time,
+    last_update: Option<Instant>,
+    time_scale: f32,
+    type_time_scales: RapidHashMap<TypeId, f32>,
     update_methods_any: AnyMap,
+    pub(crate) schedule: Schedule,
+    pub(crate) draw_methods_any: AnyMap,
 
     event_bus: RefCell<EventBus>,
 
-    events: EventQueue,
+    pub(crate) events: EventQueue,
+    pub(crate) channels: Channels,
+    commands: Commands,
+    // actors added by `add_actor` with their IDs handed out, stored ahead of other commands
+    spawns: Commands,
     pub(crate)physics: Physics,
     singletons: AnyMap,
 
     pub(crate) current_actor: Option<TypedID>,
+    // solid being moved by `move_solid`, the actors it pushes and carries pass through it
+    pub(crate) moving_solid: Option<TypedID>,
 }
 
 impl Default for World {
@@ -36,34 +61,55 @@ impl World {
         Self {
             registry: Registry::new(),
             update_methods_any: AnyMap::new(),
+            schedule: Schedule::new(),
+            draw_methods_any: AnyMap::new(),
             logic_update: Duration::from_millis(16),
+            fixed_timestep: 1.0 / 60.0,
+            accumulator: 0.0,
+            frame_delta: None,
+            time: Time::new(),
+            last_update: None,
+            time_scale: 1.0,
+            type_time_scales: RapidHashMap::default(),
             physics: Physics::new(AABB { min: vec2(-2048.0, -2048.0), max: vec2(2048.0, 2048.0) }),
             event_bus: RefCell::new(EventBus::new()),
             events: EventQueue::new(),
+            channels: Channels::new(),
+            commands: Commands::new(),
+            spawns: Commands::new(),
             singletons: AnyMap::new(),
 
             current_actor: None,
+            moving_solid: None,
         }
     }
 
-    pub fn subscribe<E: 'static, T: 'static, L: 'static>(&mut self, emitter: ID<T>, listener: ID<L>, closure: impl Fn(&mut World, &E) + 'static) {
-        self.events.subscribe(emitter, listener, closure);
+    /**
+    Calls `closure` whenever `emitter` emits an event of type `E`.
+
+    The subscription is dropped automatically when either the emitter or the listener
+    is removed from the world, or early by passing the returned handle to `unsubscribe`.
+    */
+    pub fn subscribe<E: 'static, T: 'static, L: 'static>(&mut self, emitter: ID<T>, listener: ID<L>, closure: impl Fn(&mut World, &E) + 'static) -> SubscriptionHandle {
+        self.events.subscribe(emitter, listener, closure)
+    }
+
+    /// Removes a subscription or broadcast handler. Returns false if it was already removed.
+    pub fn unsubscribe(&mut self, handle: SubscriptionHandle) -> bool {
+        self.events.unsubscribe(handle) || self.channels.unsubscribe(handle)
     }
 
     pub(crate) fn emit<E: 'static, T: 'static>(&mut self, emitter: ID<T>, event: E) {
-        let mut drained = if let Some(e) = self.events.get_listeners::<E, T>(emitter) {
-            std::mem::take(&mut e.listeners)
-        } else {
-            Vec::new()
-        };
+        let drained = self.events.take_listeners::<E, T>(emitter);
+        if drained.is_empty() { return }
 
-        for f in &drained {
-            f(self, &event);
+        for listener in &drained {
+            // skip listeners unsubscribed by an earlier listener of this same event
+            if !self.events.is_subscribed(listener.handle) { continue }
+            (listener.closure)(self, &event);
         }
 
-        if let Some(e) = self.events.get_listeners::<E, T>(emitter) {
-            e.listeners.append(&mut drained);
-        }
+        self.events.restore_listeners::<E, T>(emitter, drained);
     }
 
     pub fn debug_get_tree(&self) -> Vec<(usize, AABB)> {
@@ -73,25 +119,51 @@ impl World {
     pub(crate) fn register_type<T: Actor<P> + 'static, P: 'static>(&mut self) {
         //self.update_methods.push(T::update_system);
         println!("INFO: registering update<{:?}> for {:?}", std::any::type_name::<P>(), std::any::type_name::<T>());
-        if !self.update_methods_any.contains::<Vec<fn(&mut World, &mut P)>>() {
-            let update_methods: Vec<fn(&mut World, &mut P)> = Vec::with_capacity(32);
-            self.update_methods_any.insert(update_methods);
+        if !self.update_methods_any.contains::<Systems<P>>() {
+            self.update_methods_any.insert(Systems::<P>::new());
         }
 
-        self.update_methods_any.get_mut::<Vec<fn(&mut World, &mut P)>>().unwrap().push(T::update_system);
+        self.update_methods_any.get_mut::<Systems<P>>().unwrap().push(SystemEntry {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            run: T::update_system,
+        });
 
-        Registry::create_entry::<T>();
+        self.register_draw_type::<T, P>();
+
+        self.registry.create_entry::<T>();
+        self.registry.vtables.insert(TypeId::of::<T>(), ActorVTable::of::<T, P>());
+        let mut handlers = MessageHandlers::<T>::new();
+        T::message_handlers(&mut handlers);
+        for (message_type, deliver) in handlers.handlers {
+            self.registry.messages.insert((TypeId::of::<T>(), message_type), deliver);
+        }
         self.physics.register_type::<T>();
     }
 
+    /**
+    Adds an actor to the world, and returns its ID right away.
+
+    The actor is stored, and its `on_spawn` hook runs, before the next queued actions run
+    or commands are applied, whichever comes first. So `with`, `with_world` and `send` on
+    the new ID reach it. Its physics body is added immediately, so `set_pos` works, but
+    `get` returns `None` until then.
+    */
     pub fn add_actor<T: Actor<P> + 'static, P: 'static>(&mut self, actor: T) -> ID<T> {
+        let id = self.reserve_actor::<T, P>();
+        self.spawns.add(move |world| world.spawn_now(id, actor));
+        id
+    }
+
+    // gives a new actor its ID and physics body, ahead of storing the actor itself
+    pub(crate) fn reserve_actor<T: Actor<P> + 'static, P: 'static>(&mut self) -> ID<T> {
         let typeid = TypeId::of::<T>();
         if !self.registry.types.contains(&typeid) {
             self.registry.types.insert(typeid);
             self.register_type::<T, P>();
         }
 
-        let id = Registry::insert_actor(actor);
+        let id = self.registry.reserve_id::<T>();
         let typed_id = id.into_typed_id();
 
         // generate default physics body for type
@@ -105,41 +177,241 @@ impl World {
         id
     }
 
+    pub(crate) fn spawn_now<T: Actor<P> + 'static, P: 'static>(&mut self, id: ID<T>, actor: T) {
+        self.registry.insert_actor(id, actor);
+
+        // lifecycle: spawn
+        let previous_actor = self.current_actor.replace(id.into_typed_id());
+        // safety: actors are only stored and removed by commands, and commands are never
+        // applied while a hook runs, so the actor stays in place during `on_spawn`
+        if let Some(entry) = unsafe { self.registry.get_mut_detached(&id) } {
+            entry.1.on_spawn(&id, self);
+        }
+        self.current_actor = previous_actor;
+    }
+
+    /**
+    Queues the actor for removal. The actor is removed, and its `on_remove` hook runs,
+    the next time commands are applied by `update_systems`.
+    */
     pub fn remove_actor<T: Actor<P> + 'static, P: 'static>(&mut self, id: &ID<T>) {
-        let id = *id;
-        self.with_world(&id, move |_ett, world| {
-            world.current_actor = Some(TypedID::from_id(id));
-            // lifecycle: removal
-            // ett.on_remove(id, world);
+        self.commands.despawn(*id);
+    }
+
+    /**
+    Queues removal of an actor of any type, ie. the `TypedID` passed to `on_collision`.
+    Does nothing if the actor's type was never spawned in this world.
+    */
+    pub fn remove_dyn(&mut self, id: TypedID) {
+        if let Some(vtable) = self.registry.vtables.get(&id.type_id) {
+            (vtable.remove)(self, id);
+        }
+    }
 
-            // remove from events system
+    /// Returns true if the actor is still in the world
+    pub fn is_alive(&self, id: TypedID) -> bool {
+        self.get_dyn(id).is_some()
+    }
 
-            // remove from actor registry
-            let actor = Registry::remove_actor(&id);
+    /**
+    Gets an immutable reference to an actor of any type. Use `downcast_ref` to get the concrete type:
+    ```ignore
+    if let Some(coin) = world.get_dyn(other).and_then(|a| a.downcast_ref::<Coin>()) { ... }
+    ```
+    */
+    pub fn get_dyn(&self, id: TypedID) -> Option<&dyn Any> {
+        let vtable = self.registry.vtables.get(&id.type_id)?;
+        (vtable.get)(self, id)
+    }
 
-            // remove from physics
-            world.physics.delete_body(&id);
+    /// Returns the full type name of the actor, if its type was spawned in this world
+    pub fn type_name(&self, id: TypedID) -> Option<&'static str> {
+        self.registry.vtables.get(&id.type_id).map(|vtable| vtable.type_name)
+    }
+
+    pub(crate) fn despawn_now<T: Actor<P> + 'static, P: 'static>(&mut self, id: ID<T>) {
+        // remove from actor registry. actors that were already removed are skipped,
+        // so on_remove only ever runs once
+        let Some(mut actor) = self.registry.remove_actor(&id) else { return };
+        let typed_id = id.into_typed_id();
+
+        // lifecycle: removal
+        let previous_actor = self.current_actor.replace(typed_id);
+        actor.on_remove(&id, self);
+        self.current_actor = previous_actor;
 
-            if actor.is_some() {
-                world.registry.recently_removed.insert(id.into_typed_id());
+        // remove from events system
+        self.events.remove_actor(typed_id);
+
+        // remove from physics
+        self.physics.delete_body(&id);
+
+        self.registry.recently_removed.insert(typed_id);
+    }
+
+    /// Returns the command buffer, for deferred spawning and removal of actors
+    pub fn commands(&mut self) -> &mut Commands {
+        &mut self.commands
+    }
+
+    /**
+    Applies all queued commands. Commands queued while applying (ie. spawning debris
+    inside `on_remove`) are applied as well.
+    */
+    pub(crate) fn apply_commands(&mut self) {
+        loop {
+            self.apply_spawns();
+            let commands = self.commands.take();
+            if commands.is_empty() { break }
+            for command in commands {
+                command(self);
+                self.flush_events();
             }
-        });
+        }
+    }
+
+    /**
+    Stores the actors added by `add_actor` so far, including any their `on_spawn` adds.
+    Like commands, only applied while no actor is borrowed: between systems, and between
+    the actions that `flush_events` runs.
+    */
+    fn apply_spawns(&mut self) {
+        loop {
+            let spawns = self.spawns.take();
+            if spawns.is_empty() { break }
+            for spawn in spawns {
+                spawn(self);
+            }
+        }
+    }
+
+    /**
+    Advances the simulation by `dt` seconds of real time, running as many fixed logic ticks
+    (`update_systems`) as fit in the accumulated time. Returns the number of ticks that ran.
+
+    Leftover time is carried over to the next call, so the simulation runs the same
+    regardless of the display's refresh rate. Use `alpha()` with `interpolated_pos()`
+    to draw actors smoothly between ticks.
+    */
+    pub fn step<P: 'static>(&mut self, dt: f32, ctx: &mut P) -> u32 {
+        // avoid a "spiral of death" after a long stall, ie. a breakpoint or window drag
+        self.accumulator += dt.clamp(0.0, MAX_FRAME_TIME) as f64;
+
+        let mut ticks = 0;
+        let fixed_timestep = self.fixed_timestep as f64;
+        self.frame_delta = Some(dt);
+        while self.accumulator >= fixed_timestep {
+            self.update_systems(ctx);
+            self.accumulator -= fixed_timestep;
+            ticks += 1;
+        }
+        self.frame_delta = None;
+        ticks
+    }
+
+    /// Length of a single logic tick run by `step()`, in seconds. Defaults to 1/60th of a second.
+    pub fn fixed_timestep(&self) -> f32 {
+        self.fixed_timestep
+    }
+
+    pub fn set_fixed_timestep(&mut self, dt: f32) {
+        assert!(dt > 0.0, "fixed timestep must be greater than zero");
+        self.fixed_timestep = dt;
+    }
+
+    /// How far between the previous and the next logic tick the current frame is, from 0.0 to 1.0
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.fixed_timestep as f64).clamp(0.0, 1.0) as f32
+    }
+
+    /**
+    Returns the actor's position blended between the previous and current logic tick, or
+    None if it has no body. Pass `world.alpha()` to get a smooth position for rendering.
+    */
+    pub fn interpolated_pos<T: 'static>(&self, id: &ID<T>, alpha: f32) -> Option<Vec2> {
+        self.physics.get_body(id).map(|body| body.interpolated_pos(alpha))
+    }
+
+    /// Frame timing for the current update. See `Time`.
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    /// Scales time for every actor type. 0.0 pauses all updates, 0.5 runs at half speed.
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.max(0.0);
+        self.time.scale = self.time_scale;
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /**
+    Scales time for a single actor type, on top of the world's time scale.
+
+    A scale of 0.0 pauses the type: its `update` (and collision hooks) stop running,
+    but its physics bodies stay in the tree, so other actors still collide with it.
+    */
+    pub fn set_time_scale_of<T: 'static>(&mut self, scale: f32) {
+        self.type_time_scales.insert(TypeId::of::<T>(), scale.max(0.0));
+    }
+
+    /// Effective time scale for an actor type, including the world's time scale
+    pub fn time_scale_of<T: 'static>(&self) -> f32 {
+        self.time_scale_of_type(TypeId::of::<T>())
+    }
+
+    pub(crate) fn time_scale_of_type(&self, type_id: TypeId) -> f32 {
+        self.type_time_scales.get(&type_id).copied().unwrap_or(1.0) * self.time_scale
+    }
+
+    pub(crate) fn set_current_time_scale(&mut self, scale: f32) {
+        self.time.scale = scale;
     }
 
     pub fn update_systems<P: 'static>(&mut self, ctx: &mut P) {
         let time = Instant::now();
 
-        if let Some(systems) = self.update_methods_any.get_mut::<Vec<fn(&mut World, &mut P)>>() {
-            for system in systems.clone() {
-                system(self, ctx);
+        // step() knows the frame delta, otherwise measure the time since the last update
+        self.time.delta = match self.frame_delta {
+            Some(dt) => dt,
+            None => self.last_update.map(|last| time.duration_since(last).as_secs_f32()).unwrap_or(self.fixed_timestep),
+        };
+        self.last_update = Some(time);
+        self.time.fixed_delta = self.fixed_timestep;
+        self.time.scale = self.time_scale;
+        // when stepping, each tick simulates exactly one fixed timestep
+        let tick = if self.frame_delta.is_some() { self.fixed_timestep } else { self.time.delta };
+        self.time.elapsed += (tick * self.time_scale) as f64;
+
+        // actors added since the last update join in before any system runs
+        self.apply_commands();
+        self.physics.store_prev_positions();
+
+        if let Some(systems) = self.update_methods_any.get_mut::<Systems<P>>() {
+            // systems run in phase order, see `World::configure`
+            systems.sort(&self.schedule);
+            for system in systems.entries.clone() {
+                (system.run)(self, ctx);
+                self.apply_commands();
             }
         } else {
             println!("WARNING: no update methods registered for the generic type {:?}", std::any::type_name::<P>());
             panic!("Please make sure the argument passed into update_systems(), \"{}\",is the same as the generic type of the actor structs", std::any::type_name::<P>());
         }
 
+        // physics step, once every actor had its say about its velocity
+        self.integrate_bodies(tick);
+        self.step_dynamics(tick);
+        self.flush_events();
+
+        self.apply_commands();
         self.physics.cleanup();
         self.registry.recently_removed.clear();
+        // broadcast events are kept for one more frame
+        self.channels.update();
+        self.time.frame += 1;
         self.logic_update = time.elapsed();
     }
 
@@ -149,12 +421,12 @@ impl World {
     You should not modify other entities directly inside update methods.
     If you need to mutate properties on another entity, queue an action on it using the `world.with()` method.
     */
-    pub fn get<'a, T: 'static>(&self, id: &'a ID<T>) -> Option<&'a T> {
-        Some(&Registry::get(id)?.1)
+    pub fn get<T: 'static>(&self, id: &ID<T>) -> Option<&T> {
+        Some(&self.registry.get(id)?.1)
     }
 
-    pub(crate) fn get_mut<'a, T: 'static>(&mut self, id: &'a ID<T>) -> Option<&'a mut T> {
-        Some(&mut Registry::get_mut(id)?.1)
+    pub(crate) fn get_mut<T: 'static>(&mut self, id: &ID<T>) -> Option<&mut T> {
+        Some(&mut self.registry.get_mut(id)?.1)
     }
 
     /**
@@ -163,7 +435,7 @@ impl World {
     pub fn with<T: 'static>(&self, id: &ID<T>, f: impl Fn(&mut T) + 'static) {
         let id = *id;
         let closure = move |world: &mut World| {
-            let entry = Registry::get_mut(&id);
+            let entry = world.registry.get_mut(&id);
             if let Some(entity) = entry {
                 world.current_actor = Some(TypedID::from_id(id));
                 f(&mut entity.1);
@@ -182,7 +454,9 @@ impl World {
     pub fn with_world<T: 'static>(&self, id: &ID<T>, f: impl Fn(&mut T, &mut World) + 'static) {
         let id = *id;
         let closure = move |world: &mut World| {
-            let entry = Registry::get_mut(&id);
+            // safety: actors are only stored and removed by commands, and commands are never
+            // applied while queued actions run, so the actor stays in place while `f` runs
+            let entry = unsafe { world.registry.get_mut_detached(&id) };
 
             if let Some(entity) = entry {
                 world.current_actor = Some(TypedID::from_id(id));
@@ -199,12 +473,53 @@ impl World {
         self.event_bus.borrow_mut().push(Box::new(closure));
     }
 
+    /**
+    Sends a message to the given actor. Messages are delivered to its `Handles<M>` implementation
+    in the order they were sent, when queued actions run (after the current update).
+    Messages sent to actors that no longer exist are dropped.
+    */
+    pub fn send<T: Handles<M>, M: 'static>(&self, id: &ID<T>, message: M) {
+        let id = id.into_typed_id();
+        let closure = move |world: &mut World| {
+            crate::entity::deliver::<T, M>(world, id, &message);
+        };
+
+        self.event_bus.borrow_mut().push(Box::new(closure));
+    }
+
+    /**
+    Sends a message to an actor of any type, ie. the `TypedID` passed to `on_collision`.
+    The actor's type has to list `M` in `Actor::message_handlers`, other messages are dropped.
+    */
+    pub fn send_dyn<M: 'static>(&self, id: TypedID, message: M) {
+        let closure = move |world: &mut World| {
+            if let Some(deliver) = world.registry.messages.get(&(id.type_id, TypeId::of::<M>())).copied() {
+                deliver(world, id, &message);
+            } else if let Some(name) = world.type_name(id) {
+                println!("WARNING: {} doesn't handle messages of type {}", name, std::any::type_name::<M>());
+            }
+        };
+
+        self.event_bus.borrow_mut().push(Box::new(closure));
+    }
+
+    /// Queues `Actor::on_squish` for an actor of any type, see `move_solid`
+    pub(crate) fn squish_dyn(&self, id: TypedID, solid: TypedID) {
+        let closure = move |world: &mut World| {
+            if let Some(vtable) = world.registry.vtables.get(&id.type_id).copied() {
+                (vtable.squish)(world, id, solid);
+            }
+        };
+
+        self.event_bus.borrow_mut().push(Box::new(closure));
+    }
+
     pub fn query<T: 'static>(&self) -> impl Iterator<Item = &(ID<T>,T)> + use<'_, T> {
-        Registry::get_entry::<T>().arena.iter().map(|(_index, item)| item)
+        self.registry.get_entry::<T>().into_iter().flat_map(|entry| entry.arena.iter().map(|(_index, item)| item))
     }
 
     pub fn query_mut<T: 'static>(&mut self) -> impl Iterator<Item = &mut (ID<T>,T)> + use<'_, T> {
-        Registry::get_entry_mut::<T>().arena.iter_mut().map(|(_index, item)| item)
+        self.registry.get_entry_mut::<T>().into_iter().flat_map(|entry| entry.arena.iter_mut().map(|(_index, item)| item))
     }
 
     pub fn get_singleton<T: 'static>(&self) -> Option<&T> {
@@ -216,6 +531,8 @@ impl World {
     }
 
     pub(crate) fn flush_events(&mut self) {
+        // actions queued on actors that were just added need them stored
+        self.apply_spawns();
         // we hoist/drain the event bus, so that when executing these,
         // new events can still be added to the bus
         let events = std::mem::take(&mut self.event_bus.borrow_mut().events);
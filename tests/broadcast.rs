@@ -0,0 +1,87 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use vaabbit::events::EventReader;
+use vaabbit::*;
+
+#[derive(Debug, PartialEq)]
+struct CoinCollected(u32);
+
+// broadcasts its value every update
+struct Coin(u32);
+
+impl Actor<()> for Coin {
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        world.broadcast(CoinCollected(self.0));
+    }
+}
+
+// counts every coin with a reader, whichever order it updates in
+struct Hud {
+    coins: EventReader<CoinCollected>,
+    total: u32,
+}
+
+impl Actor<()> for Hud {
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.total += self.coins.read(world).map(|coin| coin.0).sum::<u32>();
+    }
+}
+
+#[test]
+fn handlers_hear_every_sender() {
+    let mut world = World::new();
+    let total = Rc::new(Cell::new(0));
+    let counted = total.clone();
+    let handle = world.on(move |_world, coin: &CoinCollected| counted.set(counted.get() + coin.0));
+
+    world.add_actor(Coin(1));
+    world.add_actor(Coin(10));
+    world.broadcast(CoinCollected(100));
+    world.update_systems(&mut ());
+    assert_eq!(total.get(), 111);
+
+    assert!(world.unsubscribe(handle));
+    world.update_systems(&mut ());
+    assert_eq!(total.get(), 111);
+}
+
+#[test]
+fn readers_see_each_event_once() {
+    let mut world = World::new();
+    let hud = world.add_actor(Hud { coins: EventReader::new(), total: 0 });
+    world.add_actor(Coin(1));
+    world.add_actor(Coin(10));
+
+    for _ in 0..5 {
+        world.update_systems(&mut ());
+    }
+    // the last update's coins are read in the next one, if the hud went first
+    let total = world.get(&hud).unwrap().total;
+    assert!(total == 55 || total == 44, "{}", total);
+    world.update_systems(&mut ());
+    assert!(world.get(&hud).unwrap().total >= 55);
+}
+
+#[test]
+fn events_are_kept_for_one_more_frame() {
+    let mut world = World::new();
+    world.add_actor(Hud { coins: EventReader::new(), total: 0 });
+    let mut early = world.reader::<CoinCollected>();
+    world.broadcast(CoinCollected(1));
+    // only sees what comes after it
+    let mut late = world.reader::<CoinCollected>();
+    assert!(late.is_empty(&world));
+
+    world.update_systems(&mut ());
+    world.broadcast(CoinCollected(2));
+    assert_eq!(late.read(&world).collect::<Vec<_>>(), [&CoinCollected(2)]);
+    assert!(late.read(&world).next().is_none());
+    assert_eq!(early.read(&world).collect::<Vec<_>>(), [&CoinCollected(1), &CoinCollected(2)]);
+
+    // read a frame too late, the first event is gone
+    world.update_systems(&mut ());
+    assert_eq!(EventReader::<CoinCollected>::new().read(&world).collect::<Vec<_>>(), [&CoinCollected(2)]);
+    world.update_systems(&mut ());
+    assert!(EventReader::<CoinCollected>::new().is_empty(&world));
+}
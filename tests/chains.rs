@@ -0,0 +1,124 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass, ALL_LAYERS};
+use vaabbit::shapes::{Collider, AABB};
+use vaabbit::*;
+
+struct Terrain;
+
+impl Actor<()> for Terrain {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, None, id, PhysicsClass::Solid)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+// a chain that only reports overlaps, like a finish line
+struct Tripwire;
+
+impl Actor<()> for Tripwire {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, None, id, PhysicsClass::Zone)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+#[derive(Default)]
+struct Crate {
+    collisions: u32,
+    touching_below: bool,
+}
+
+impl Actor<()> for Crate {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        // straddles the point where the two flat edges meet
+        PhysicsBody::new(Vec2::new(28.0, 20.0), Collider::aabb(Vec2::new(28.0, 20.0), Vec2::splat(8.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.touching_below = self.move_and_slide(&Vec2::new(0.0, -3.0), world).touching_below;
+    }
+
+    fn on_collision(&mut self, _id: &ID<Self>, _other: TypedID, _world: &mut World) {
+        self.collisions += 1;
+    }
+}
+
+const FLOOR: [Vec2; 3] = [Vec2::new(0.0, 0.0), Vec2::new(32.0, 0.0), Vec2::new(200.0, 0.0)];
+
+#[test]
+fn actors_rest_on_chains() {
+    let mut world = World::new();
+    let terrain = world.add_actor(Terrain);
+    world.set_chain(&terrain, &FLOOR, false);
+    let crate_id = world.add_actor(Crate::default());
+
+    // the tree is rebuilt every update, the edges have to stay findable
+    for _ in 0..30 {
+        world.update_systems(&mut ());
+    }
+
+    assert_eq!(world.get_pos(&crate_id), Vec2::new(28.0, 0.0));
+    assert!(world.get(&crate_id).unwrap().touching_below);
+}
+
+#[test]
+fn chains_report_one_collision_per_actor() {
+    let mut world = World::new();
+    let tripwire = world.add_actor(Tripwire);
+    world.set_chain(&tripwire, &FLOOR.map(|point| point + Vec2::new(0.0, 10.0)), false);
+    let crate_id = world.add_actor(Crate::default());
+
+    // falls through, overlapping both edges at once on the way
+    for _ in 0..10 {
+        world.update_systems(&mut ());
+    }
+
+    // once for each of the two moves that end overlapping it, not once per edge
+    assert!(world.get_pos(&crate_id).y < 0.0);
+    assert_eq!(world.get(&crate_id).unwrap().collisions, 2);
+}
+
+#[test]
+fn chains_are_replaced_and_removed_with_their_actor() {
+    let mut world = World::new();
+    let terrain = world.add_actor(Terrain);
+    let solid = |body: &PhysicsBody| body.class == PhysicsClass::Solid;
+
+    world.set_chain(&terrain, &FLOOR, false);
+    world.update_systems(&mut ());
+    assert_eq!(world.raycast(Vec2::new(100.0, 10.0), Vec2::NEG_Y, 20.0, solid).map(|hit| hit.distance), Some(10.0));
+    assert_eq!(world.query_area(&AABB::new(Vec2::new(90.0, -5.0), Vec2::new(110.0, 5.0)), ALL_LAYERS).len(), 1);
+
+    // a closed triangle instead, the old floor is gone
+    world.set_chain(&terrain, &[Vec2::new(0.0, 0.0), Vec2::new(40.0, 0.0), Vec2::new(0.0, 40.0)], true);
+    world.update_systems(&mut ());
+    assert!(world.raycast(Vec2::new(100.0, 10.0), Vec2::NEG_Y, 20.0, solid).is_none());
+    assert_eq!(world.raycast(Vec2::new(-10.0, 20.0), Vec2::X, 20.0, solid).map(|hit| hit.distance), Some(10.0));
+
+    world.remove_actor(&terrain);
+    world.update_systems(&mut ());
+    assert!(world.raycast(Vec2::new(-10.0, 20.0), Vec2::X, 20.0, solid).is_none());
+    assert!(world.query_area(&AABB::new(Vec2::new(-50.0, -50.0), Vec2::new(50.0, 50.0)), ALL_LAYERS).is_empty());
+}
+
+#[test]
+fn chains_follow_their_actors_layers() {
+    let mut world = World::new();
+    let terrain = world.add_actor(Terrain);
+    world.set_chain(&terrain, &FLOOR, false);
+    let crate_id = world.add_actor(Crate::default());
+    let area = AABB::new(Vec2::new(90.0, -5.0), Vec2::new(110.0, 5.0));
+
+    // on no layer, so nothing collides with it anymore
+    world.set_collision_layers(&terrain, 0, ALL_LAYERS);
+    assert!(world.query_area(&area, ALL_LAYERS).is_empty());
+    for _ in 0..10 {
+        world.update_systems(&mut ());
+    }
+    assert!(world.get_pos(&crate_id).y < 0.0);
+
+    world.set_collision_layers(&terrain, 1 << 4, ALL_LAYERS);
+    assert_eq!(world.query_area(&area, 1 << 4).len(), 1);
+    assert!(world.query_area(&area, ALL_LAYERS & !(1 << 4)).is_empty());
+}
@@ -0,0 +1,35 @@
+// fixtures shared by the tests, each test file uses only some of them
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+// builds the body of the next actor added with `spawn`, since `init_physicsbody` doesn't see the actor
+type BodyFn = Box<dyn FnOnce(TypedID) -> PhysicsBody>;
+
+thread_local! {
+    static NEXT_BODY: RefCell<Option<BodyFn>> = RefCell::new(None);
+}
+
+// adds `actor`, with the body `build` returns from its `init_physicsbody`, see `next_body`
+pub fn spawn<T: Actor<()>>(world: &mut World, actor: T, build: impl FnOnce(TypedID) -> PhysicsBody + 'static) -> ID<T> {
+    NEXT_BODY.with(|next| *next.borrow_mut() = Some(Box::new(build)));
+    world.add_actor(actor)
+}
+
+// the body of an actor added with `spawn`, for its `init_physicsbody`
+pub fn next_body(id: TypedID) -> PhysicsBody {
+    NEXT_BODY.with(|next| next.borrow_mut().take().expect("actor not added with `spawn`"))(id)
+}
+
+// a body placed where `collider` is: by the center of circles, the corner of other shapes
+pub fn placed(collider: Option<Collider>, class: PhysicsClass) -> impl FnOnce(TypedID) -> PhysicsBody {
+    let pos = match collider {
+        Some(Collider::CIRCLE(circle)) => circle.pos,
+        _ => collider.map(|collider| collider.bounds().min).unwrap_or_default(),
+    };
+    move |id| PhysicsBody::new(pos, collider, id, class)
+}
@@ -0,0 +1,105 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::{Collider, Contact, Shape};
+use vaabbit::*;
+
+// one of every collider, around `center`
+fn shapes(center: Vec2) -> Vec<(&'static str, Collider)> {
+    let hexagon = [Vec2::new(-4.0, 0.0), Vec2::new(-2.0, -3.0), Vec2::new(2.0, -3.0), Vec2::new(4.0, 0.0), Vec2::new(2.0, 3.0), Vec2::new(-2.0, 3.0)];
+    vec![
+        ("aabb", Collider::aabb(center - Vec2::splat(4.0), Vec2::splat(8.0)).unwrap()),
+        ("circle", Collider::circle(center, 4.0).unwrap()),
+        ("polygon", Collider::polygon(center, &hexagon).unwrap()),
+        ("capsule", Collider::capsule(center, 2.0, 3.0).unwrap()),
+        ("obb", Collider::obb(center, Vec2::new(8.0, 6.0), 0.5).unwrap()),
+    ]
+}
+
+#[test]
+fn contacts_separate_every_pair() {
+    for (a_name, a) in shapes(Vec2::new(3.0, 1.0)) {
+        for (b_name, b) in shapes(Vec2::ZERO) {
+            let name = format!("{} against {}", a_name, b_name);
+            let Some(contact) = a.contact(&b) else { panic!("{}: no contact", name) };
+            assert!(contact.depth > 0.0, "{}: {:?}", name, contact);
+            assert!((contact.normal.length() - 1.0).abs() < 0.001, "{}: {:?}", name, contact);
+
+            // moving out along the normal by the depth separates them, any less doesn't
+            let mut out = a;
+            out.translate(contact.normal * (contact.depth + 0.01));
+            assert!(!out.overlaps(&b), "{}: {:?}", name, contact);
+            let mut part_way = a;
+            part_way.translate(contact.normal * (contact.depth - 0.01));
+            assert!(part_way.overlaps(&b), "{}: {:?}", name, contact);
+
+            // the point is in both
+            let point = Collider::circle(contact.point, 0.01).unwrap();
+            assert!(point.overlaps(&a) && point.overlaps(&b), "{}: {:?}", name, contact);
+
+            // the same contact from the other side
+            let other = b.contact(&a).unwrap();
+            assert!(other.normal.abs_diff_eq(-contact.normal, 0.001), "{}: {:?} and {:?}", name, contact, other);
+            assert!((other.depth - contact.depth).abs() < 0.001, "{}: {:?} and {:?}", name, contact, other);
+        }
+    }
+}
+
+#[test]
+fn separated_shapes_have_no_contact() {
+    for (a_name, a) in shapes(Vec2::new(20.0, 1.0)) {
+        for (b_name, b) in shapes(Vec2::ZERO) {
+            assert!(a.contact(&b).is_none(), "{} against {}", a_name, b_name);
+        }
+    }
+}
+
+// walks right into the spikes, and keeps the contacts it was told about
+#[derive(Default)]
+struct Walker {
+    contacts: Vec<(TypedID, Contact)>,
+}
+
+impl Actor<()> for Walker {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, Collider::aabb(Vec2::ZERO, Vec2::splat(8.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.move_and_slide(&Vec2::new(3.0, 0.0), world);
+    }
+
+    fn on_contact(&mut self, _id: &ID<Self>, other: TypedID, contact: Contact, _world: &mut World) {
+        self.contacts.push((other, contact));
+    }
+}
+
+struct Spikes;
+
+impl Actor<()> for Spikes {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(13.0, 0.0), Collider::aabb(Vec2::new(13.0, 0.0), Vec2::splat(8.0)), id, PhysicsClass::Zone)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+#[test]
+fn actors_are_told_about_contacts() {
+    let mut world = World::new();
+    let spikes = world.add_actor(Spikes);
+    let walker = world.add_actor(Walker::default());
+    world.update_systems(&mut ());
+    assert!(world.contacts(&walker).is_empty());
+
+    // now 1 pixel in
+    world.update_systems(&mut ());
+    let contacts = &world.get(&walker).unwrap().contacts;
+    assert_eq!(contacts.len(), 1);
+    let (other, contact) = contacts[0];
+    assert_eq!(other, spikes.into_typed_id());
+    assert_eq!(contact.normal, Vec2::NEG_X);
+    assert_eq!(contact.depth, 1.0);
+
+    // the same from the world, and flipped for the spikes
+    assert_eq!(world.contacts(&walker).as_slice(), [(spikes.into_typed_id(), contact)]);
+    assert_eq!(world.contacts(&spikes).as_slice(), [(walker.into_typed_id(), contact.flipped())]);
+}
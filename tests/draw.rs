@@ -0,0 +1,74 @@
+use vaabbit::*;
+
+// what was drawn, in order
+type Canvas = Vec<String>;
+
+struct Ground;
+struct Player;
+// sorted by height with +Y up, so lower trees are drawn in front
+struct Tree {
+    y: f32,
+}
+
+impl Actor<Canvas> for Ground {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _canvas: &mut Canvas) {}
+
+    fn draw(&self, _id: &ID<Self>, _world: &World, canvas: &mut Canvas) {
+        canvas.push("ground".to_string());
+    }
+
+    fn draw_order(&self, _id: &ID<Self>, _world: &World) -> f32 {
+        -1.0
+    }
+}
+
+impl Actor<Canvas> for Player {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _canvas: &mut Canvas) {}
+
+    fn draw(&self, _id: &ID<Self>, _world: &World, canvas: &mut Canvas) {
+        canvas.push("player".to_string());
+    }
+}
+
+impl Actor<Canvas> for Tree {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _canvas: &mut Canvas) {}
+
+    fn draw(&self, _id: &ID<Self>, _world: &World, canvas: &mut Canvas) {
+        canvas.push(format!("tree {}", self.y));
+    }
+
+    fn draw_order(&self, _id: &ID<Self>, _world: &World) -> f32 {
+        -self.y
+    }
+}
+
+fn draw(world: &mut World) -> Canvas {
+    let mut canvas = Canvas::new();
+    world.draw_systems(&mut canvas);
+    canvas
+}
+
+#[test]
+fn actors_are_drawn_by_draw_order_across_types() {
+    let mut world = World::new();
+    world.add_actor(Tree { y: -10.0 });
+    world.add_actor(Player);
+    world.add_actor(Tree { y: 20.0 });
+    world.add_actor(Ground);
+    world.add_actor(Tree { y: 5.0 });
+    world.update_systems(&mut Canvas::new());
+
+    assert_eq!(draw(&mut world), ["tree 20", "tree 5", "ground", "player", "tree -10"]);
+}
+
+#[test]
+fn paused_actors_are_still_drawn() {
+    let mut world = World::new();
+    world.add_actor(Player);
+    world.add_actor(Ground);
+    world.update_systems(&mut Canvas::new());
+
+    world.set_time_scale_of::<Player>(0.0);
+    world.set_time_scale(0.0);
+    assert_eq!(draw(&mut world), ["ground", "player"]);
+}
@@ -0,0 +1,156 @@
+use std::cell::Cell;
+
+use vaabbit::physics::{PhysicsBody, PhysicsClass, Tile, TileMapCollider};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+mod common;
+
+const DT: f32 = 1.0 / 60.0;
+
+thread_local! {
+    // movement of every `Pusher` per update
+    static PUSH: Cell<Vec2> = const { Cell::new(Vec2::ZERO) };
+}
+
+struct Body;
+
+impl Actor<()> for Body {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+struct Pusher;
+
+impl Actor<()> for Pusher {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, Collider::aabb(Vec2::ZERO, Vec2::new(8.0, 16.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        let push = PUSH.with(|push| push.get());
+        self.move_and_slide(&push, world);
+    }
+}
+
+fn add(world: &mut World, build: impl FnOnce(TypedID) -> PhysicsBody + 'static) -> ID<Body> {
+    common::spawn(world, Body, build)
+}
+
+fn solid(world: &mut World, pos: Vec2, size: Vec2) -> ID<Body> {
+    add(world, move |id| PhysicsBody::new(pos, Collider::aabb(pos, size), id, PhysicsClass::Solid))
+}
+
+fn crate_at(world: &mut World, pos: Vec2, size: Vec2) -> ID<Body> {
+    add(world, move |id| PhysicsBody::new(pos, Collider::aabb(pos, size), id, PhysicsClass::Dynamic))
+}
+
+fn ball_at(world: &mut World, pos: Vec2, restitution: f32) -> ID<Body> {
+    add(world, move |id| PhysicsBody::new(pos, Collider::circle(pos, 4.0), id, PhysicsClass::Dynamic).with_restitution(restitution))
+}
+
+fn run(world: &mut World, seconds: f32) {
+    for _ in 0..(seconds / DT).round() as u32 {
+        world.step(DT, &mut ());
+    }
+}
+
+#[test]
+fn crates_stack_and_come_to_rest() {
+    let mut world = World::new();
+    world.set_gravity(Vec2::new(0.0, -600.0));
+    solid(&mut world, Vec2::new(-100.0, -10.0), Vec2::new(300.0, 10.0));
+    let crates: Vec<_> = (0..3).map(|i| crate_at(&mut world, Vec2::new(0.0, 20.0 + i as f32 * 20.0), Vec2::splat(16.0))).collect();
+
+    run(&mut world, 5.0);
+
+    for (i, crate_id) in crates.iter().enumerate() {
+        let pos = world.get_pos(crate_id);
+        // resting on each other, sunk in by less than a pixel
+        assert!((pos.y - i as f32 * 16.0).abs() < 1.0, "crate {} at {}", i, pos);
+        assert!(pos.x.abs() < 0.01, "crate {} at {}", i, pos);
+        // what is left of the 10 gravity adds every update
+        assert!(world.velocity(crate_id).length() < 2.0, "crate {} moving at {}", i, world.velocity(crate_id));
+    }
+}
+
+#[test]
+fn balls_bounce_by_their_restitution() {
+    let mut world = World::new();
+    world.set_gravity(Vec2::new(0.0, -600.0));
+    solid(&mut world, Vec2::new(-100.0, -10.0), Vec2::new(300.0, 10.0));
+    let bouncy = ball_at(&mut world, Vec2::new(0.0, 104.0), 0.8);
+    let dead = ball_at(&mut world, Vec2::new(50.0, 104.0), 0.0);
+
+    // highest point after the first bounce
+    let mut apex = None;
+    for _ in 0..120 {
+        world.step(DT, &mut ());
+        if world.velocity(&bouncy).y > 0.0 || apex.is_some() {
+            apex = Some(world.get_pos(&bouncy).y.max(apex.unwrap_or(0.0)));
+        }
+    }
+
+    // a fall of 100 pixels bounces back up to 0.8² of it, give or take a step
+    let apex = apex.unwrap() - 4.0;
+    assert!((apex - 64.0).abs() < 6.0, "bounced up to {}", apex);
+    assert!((world.get_pos(&dead).y - 4.0).abs() < 0.5);
+    assert!(world.velocity(&dead).length() < 1.0);
+}
+
+#[test]
+fn fast_bodies_stop_at_thin_solids() {
+    let mut world = World::new();
+    world.set_gravity(Vec2::new(0.0, -600.0));
+    // one pixel thick, a tenth of what the crates move per update
+    solid(&mut world, Vec2::new(-100.0, -1.0), Vec2::new(300.0, 1.0));
+    let terrain = add(&mut world, |id| PhysicsBody::new(Vec2::ZERO, None, id, PhysicsClass::Solid));
+    world.set_chain(&terrain, &[Vec2::new(200.0, 0.0), Vec2::new(400.0, 0.0)], false);
+    let tilemap = add(&mut world, |id| PhysicsBody::new(Vec2::ZERO, None, id, PhysicsClass::Solid));
+    let mut map = TileMapCollider::new(Vec2::new(400.0, -16.0), Vec2::splat(16.0), 10, 1);
+    for x in 0..10 {
+        map.set(IVec2::new(x, 0), Some(Tile::solid()));
+    }
+    world.set_tilemap(&tilemap, map);
+
+    let crates = [100.0, 300.0, 480.0].map(|x| {
+        let crate_id = crate_at(&mut world, Vec2::new(x, 300.0), Vec2::splat(8.0));
+        world.set_velocity(&crate_id, Vec2::new(0.0, -600.0));
+        crate_id
+    });
+
+    for _ in 0..60 {
+        world.step(DT, &mut ());
+        for crate_id in &crates {
+            // never sunk in further than the slop
+            assert!(world.get_pos(crate_id).y > -0.2, "crate at {}", world.get_pos(crate_id));
+        }
+    }
+    for crate_id in &crates {
+        assert!(world.get_pos(crate_id).y.abs() < 0.2, "crate at {}", world.get_pos(crate_id));
+    }
+}
+
+#[test]
+fn actors_push_crates() {
+    let mut world = World::new();
+    world.set_gravity(Vec2::new(0.0, -600.0));
+    solid(&mut world, Vec2::new(-100.0, -10.0), Vec2::new(400.0, 10.0));
+    let crate_id = crate_at(&mut world, Vec2::new(20.0, 0.0), Vec2::splat(16.0));
+    PUSH.with(|push| push.set(Vec2::new(1.0, -1.0)));
+    let pusher = world.add_actor(Pusher);
+
+    for i in 1..=40 {
+        world.step(DT, &mut ());
+        // crates don't block actors
+        assert_eq!(world.get_pos(&pusher), Vec2::new(i as f32, 0.0));
+    }
+
+    // kept in front of the actor, which reached x 40
+    let pos = world.get_pos(&crate_id);
+    assert!(pos.x > 40.0 && pos.x < 50.0, "crate at {}", pos);
+    assert!(pos.y.abs() < 0.5, "crate at {}", pos);
+}
@@ -0,0 +1,96 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass, ALL_LAYERS, DEFAULT_LAYER};
+use vaabbit::shapes::{Collider, AABB};
+use vaabbit::*;
+
+mod common;
+
+const BULLET: u32 = 1 << 1;
+const ENEMY: u32 = 1 << 2;
+const GLASS: u32 = 1 << 3;
+
+// flies right, hitting everything but other bullets and glass
+#[derive(Default)]
+struct Bullet {
+    hits: Vec<TypedID>,
+}
+
+impl Actor<()> for Bullet {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id).with_layers(BULLET, ALL_LAYERS & !BULLET & !GLASS)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.move_and_slide(&Vec2::new(4.0, 0.0), world);
+    }
+
+    fn on_collision(&mut self, _id: &ID<Self>, other: TypedID, _world: &mut World) {
+        self.hits.push(other);
+    }
+}
+
+struct Enemy;
+
+impl Actor<()> for Enemy {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(40.0, -4.0), Collider::aabb(Vec2::new(40.0, -4.0), Vec2::splat(8.0)), id, PhysicsClass::Zone)
+            .with_layers(ENEMY, ALL_LAYERS)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+// in front of the enemy, but only stops what collides with glass
+struct Window;
+
+impl Actor<()> for Window {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(20.0, -10.0), Collider::aabb(Vec2::new(20.0, -10.0), Vec2::new(2.0, 20.0)), id, PhysicsClass::Solid)
+            .with_layers(GLASS, ALL_LAYERS)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+fn bullet(world: &mut World, pos: Vec2) -> ID<Bullet> {
+    common::spawn(world, Bullet::default(), move |id| PhysicsBody::new(pos, Collider::aabb(pos, Vec2::splat(2.0)), id, PhysicsClass::Actor))
+}
+
+#[test]
+fn bullets_pass_through_what_their_mask_leaves_out() {
+    let mut world = World::new();
+    let enemy = world.add_actor(Enemy);
+    let window = world.add_actor(Window);
+    // a second bullet right behind the first, overlapping it all the way
+    let first = bullet(&mut world, Vec2::new(1.0, 0.0));
+    let second = bullet(&mut world, Vec2::ZERO);
+
+    for _ in 0..15 {
+        world.update_systems(&mut ());
+    }
+
+    // through the window, which collides with bullets but isn't in their mask, into the enemy
+    assert_eq!(world.get_pos(&first).x, 61.0);
+    for bullet in [first, second] {
+        let hits = &world.get(&bullet).unwrap().hits;
+        assert!(hits.contains(&enemy.into_typed_id()));
+        assert!(!hits.contains(&window.into_typed_id()));
+        assert!(hits.iter().all(|hit| hit.is::<Bullet>().is_none()));
+    }
+}
+
+#[test]
+fn queries_only_find_bodies_on_the_mask() {
+    let mut world = World::new();
+    let enemy = world.add_actor(Enemy);
+    let window = world.add_actor(Window);
+    let bullet = bullet(&mut world, Vec2::new(30.0, 0.0));
+    world.update_systems(&mut ());
+    let area = AABB::new(Vec2::new(0.0, -20.0), Vec2::new(60.0, 20.0));
+
+    assert_eq!(world.query_area(&area, ALL_LAYERS).len(), 3);
+    assert_eq!(world.query_area(&area, ENEMY).as_slice(), [enemy.into_typed_id()]);
+    assert_eq!(world.query_area(&area, GLASS | BULLET).len(), 2);
+    assert!(world.query_area(&area, DEFAULT_LAYER).is_empty());
+    assert!(!world.query_area(&area, ALL_LAYERS & !GLASS).contains(&window.into_typed_id()));
+    assert!(world.query_area(&area, BULLET).contains(&bullet.into_typed_id()));
+}
@@ -0,0 +1,170 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+// records every lifecycle hook, in the order they ran
+type Log = Rc<RefCell<Vec<String>>>;
+
+struct Coin {
+    log: Log,
+}
+
+impl Actor<()> for Coin {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, Collider::aabb(Vec2::ZERO, Vec2::splat(8.0)), id, PhysicsClass::Zone)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+
+    fn on_spawn(&mut self, _id: &ID<Self>, _world: &mut World) {
+        self.log.borrow_mut().push("spawn".into());
+    }
+
+    fn on_remove(&mut self, _id: &ID<Self>, _world: &mut World) {
+        self.log.borrow_mut().push("remove".into());
+    }
+
+    fn on_collision(&mut self, id: &ID<Self>, _other: TypedID, world: &mut World) {
+        // removing twice, from inside the actor's own hook
+        world.remove_actor(id);
+        world.remove_actor(id);
+    }
+}
+
+struct Player;
+
+impl Actor<()> for Player {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(20.0, 0.0), Collider::aabb(Vec2::new(20.0, 0.0), Vec2::splat(8.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.move_and_slide(&Vec2::new(-10.0, 0.0), world);
+    }
+}
+
+// spawns copies of itself from `on_spawn`, while it is borrowed by the hook
+struct Spawner {
+    generation: u32,
+    log: Log,
+}
+
+impl Actor<()> for Spawner {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+
+    fn on_spawn(&mut self, _id: &ID<Self>, world: &mut World) {
+        self.log.borrow_mut().push(format!("spawn {}", self.generation));
+        if self.generation < 3 {
+            // enough to grow the storage of the type past its initial capacity
+            for _ in 0..1500 {
+                world.add_actor(Spawner { generation: 3, log: Log::default() });
+            }
+            world.add_actor(Spawner { generation: self.generation + 1, log: self.log.clone() });
+        }
+        // still readable after the spawns above
+        self.log.borrow_mut().push(format!("done {}", self.generation));
+    }
+}
+
+// lays a coin every update, and marks it right away through its new ID
+struct Nest {
+    log: Log,
+}
+
+impl Actor<()> for Nest {
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        let coin = world.add_actor(Coin { log: self.log.clone() });
+        world.with(&coin, |coin: &mut Coin| coin.log.borrow_mut().push("with".into()));
+        world.with_world(&coin, move |actor: &mut Coin, world| {
+            actor.log.borrow_mut().push("with_world".into());
+            world.set_pos(coin, Vec2::new(-100.0, 0.0));
+        });
+    }
+}
+
+#[test]
+fn add_actor_is_deferred_until_commands_apply() {
+    let log = Log::default();
+    let mut world = World::new();
+    let id = world.add_actor(Coin { log: log.clone() });
+
+    // the body exists right away, the actor itself only once commands are applied
+    world.set_pos(id, Vec2::new(100.0, 100.0));
+    assert_eq!(world.get_pos(&id), Vec2::new(100.0, 100.0));
+    assert!(world.get(&id).is_none());
+    assert!(!world.is_alive(id.into()));
+    assert!(log.borrow().is_empty());
+
+    world.update_systems(&mut ());
+    assert!(world.get(&id).is_some());
+    assert_eq!(*log.borrow(), ["spawn"]);
+}
+
+#[test]
+fn spawn_and_remove_hooks_run_once() {
+    let log = Log::default();
+    let other_log = Log::default();
+    let mut world = World::new();
+    let coin = world.add_actor(Coin { log: log.clone() });
+    world.add_actor(Player);
+    world.commands().spawn(Coin { log: other_log.clone() });
+
+    for _ in 0..5 {
+        world.update_systems(&mut ());
+    }
+
+    assert!(world.get(&coin).is_none());
+    assert_eq!(*log.borrow(), ["spawn", "remove"]);
+    assert_eq!(*other_log.borrow(), ["spawn", "remove"]);
+    assert_eq!(world.query::<Coin>().count(), 0);
+}
+
+#[test]
+fn spawning_inside_on_spawn_is_deferred() {
+    let log = Log::default();
+    let mut world = World::new();
+    world.add_actor(Spawner { generation: 0, log: log.clone() });
+    world.update_systems(&mut ());
+
+    assert_eq!(*log.borrow(), ["spawn 0", "done 0", "spawn 1", "done 1", "spawn 2", "done 2", "spawn 3", "done 3"]);
+    assert_eq!(world.query::<Spawner>().count(), 1 + 3 * 1501);
+}
+
+#[test]
+fn removed_ids_are_not_reused() {
+    let log = Log::default();
+    let mut world = World::new();
+    let first = world.add_actor(Coin { log: log.clone() });
+    world.update_systems(&mut ());
+    world.remove_actor(&first);
+    world.update_systems(&mut ());
+
+    let second = world.add_actor(Coin { log: log.clone() });
+    world.update_systems(&mut ());
+    assert_ne!(first, second);
+    assert!(world.get(&first).is_none());
+    assert!(world.get(&second).is_some());
+}
+
+#[test]
+fn actions_reach_actors_added_the_same_frame() {
+    let log = Log::default();
+    let mut world = World::new();
+    let coin = world.add_actor(Coin { log: log.clone() });
+    world.with(&coin, |coin: &mut Coin| coin.log.borrow_mut().push("with".into()));
+    world.with_world(&coin, |coin: &mut Coin, _world| coin.log.borrow_mut().push("with_world".into()));
+    world.update_systems(&mut ());
+    assert_eq!(*log.borrow(), ["spawn", "with", "with_world"]);
+
+    // and from inside an update
+    let log = Log::default();
+    let mut world = World::new();
+    world.add_actor(Nest { log: log.clone() });
+    world.update_systems(&mut ());
+    assert_eq!(*log.borrow(), ["spawn", "with", "with_world"]);
+    let (coin, _) = world.query::<Coin>().next().unwrap();
+    assert_eq!(world.get_pos(coin), Vec2::new(-100.0, 0.0));
+}
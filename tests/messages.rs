@@ -0,0 +1,126 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+struct Damage(u32);
+struct Heal(u32);
+struct Unhandled;
+
+struct Enemy {
+    health: u32,
+    received: Vec<&'static str>,
+}
+
+impl Actor<()> for Enemy {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, Collider::aabb(Vec2::ZERO, Vec2::splat(8.0)), id, PhysicsClass::Zone)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+
+    fn message_handlers(handlers: &mut MessageHandlers<Self>) {
+        handlers.add::<Damage>().add::<Heal>();
+    }
+}
+
+impl Handles<Damage> for Enemy {
+    fn on_message(&mut self, id: &ID<Self>, damage: &Damage, world: &mut World) {
+        self.health = self.health.saturating_sub(damage.0);
+        self.received.push("damage");
+        if self.health == 0 {
+            world.remove_actor(id);
+        }
+    }
+}
+
+impl Handles<Heal> for Enemy {
+    fn on_message(&mut self, _id: &ID<Self>, heal: &Heal, _world: &mut World) {
+        self.health += heal.0;
+        self.received.push("heal");
+    }
+}
+
+// hits whatever it touches, without knowing its type
+struct Bullet;
+
+impl Actor<()> for Bullet {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(20.0, 0.0), Collider::aabb(Vec2::new(20.0, 0.0), Vec2::splat(2.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.move_and_slide(&Vec2::new(-8.0, 0.0), world);
+    }
+
+    fn on_collision(&mut self, _id: &ID<Self>, other: TypedID, world: &mut World) {
+        world.send_dyn(other, Damage(4));
+        world.send_dyn(other, Unhandled);
+    }
+}
+
+#[test]
+fn messages_arrive_in_order() {
+    let mut world = World::new();
+    let enemy = world.add_actor(Enemy { health: 10, received: Vec::new() });
+    world.update_systems(&mut ());
+
+    world.send(&enemy, Damage(3));
+    world.send(&enemy, Heal(5));
+    world.send_dyn(enemy.into(), Damage(1));
+    world.update_systems(&mut ());
+
+    let enemy = world.get(&enemy).unwrap();
+    assert_eq!(enemy.health, 11);
+    assert_eq!(enemy.received, ["damage", "heal", "damage"]);
+}
+
+#[test]
+fn collision_handlers_message_what_they_hit() {
+    let mut world = World::new();
+    let enemy = world.add_actor(Enemy { health: 6, received: Vec::new() });
+    world.add_actor(Bullet);
+
+    world.update_systems(&mut ());
+    world.update_systems(&mut ());
+    assert_eq!(world.get(&enemy).unwrap().health, 2);
+
+    // messages to a removed actor are dropped
+    world.send(&enemy, Damage(2));
+    world.send(&enemy, Damage(2));
+    world.update_systems(&mut ());
+    assert!(world.get(&enemy).is_none());
+    world.send(&enemy, Heal(1));
+    world.update_systems(&mut ());
+}
+
+// adds an enemy in its first update, and messages it through the new ID
+struct Barracks;
+
+impl Actor<()> for Barracks {
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        if world.query::<Enemy>().next().is_some() { return }
+        let enemy = world.add_actor(Enemy { health: 10, received: Vec::new() });
+        world.send(&enemy, Damage(3));
+        world.send_dyn(enemy.into(), Heal(1));
+    }
+}
+
+#[test]
+fn messages_reach_actors_added_the_same_frame() {
+    let mut world = World::new();
+    let enemy = world.add_actor(Enemy { health: 10, received: Vec::new() });
+    world.send(&enemy, Damage(3));
+    world.send_dyn(enemy.into(), Heal(1));
+    world.update_systems(&mut ());
+    let enemy = world.get(&enemy).unwrap();
+    assert_eq!(enemy.health, 8);
+    assert_eq!(enemy.received, ["damage", "heal"]);
+
+    // and from inside an update
+    let mut world = World::new();
+    world.add_actor(Barracks);
+    world.update_systems(&mut ());
+    let (_, enemy) = world.query::<Enemy>().next().unwrap();
+    assert_eq!(enemy.health, 8);
+    assert_eq!(enemy.received, ["damage", "heal"]);
+}
@@ -0,0 +1,108 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+mod common;
+
+const TICK: f32 = 1.0 / 60.0;
+
+type Setup = fn(PhysicsBody) -> PhysicsBody;
+
+// moved by the world only
+struct Crate;
+
+impl Actor<()> for Crate {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+// a floor with its top at y 0
+struct Floor;
+
+impl Actor<()> for Floor {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(-100.0, -16.0), Collider::aabb(Vec2::new(-100.0, -16.0), Vec2::new(300.0, 16.0)), id, PhysicsClass::Solid)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+// a crate at `pos`, given its motion by `setup`
+fn add_crate(world: &mut World, pos: Vec2, setup: Setup) -> ID<Crate> {
+    common::spawn(world, Crate, move |id| setup(PhysicsBody::new(pos, Collider::aabb(pos, Vec2::splat(8.0)), id, PhysicsClass::Actor)))
+}
+
+fn ticks(world: &mut World, count: u32) {
+    for _ in 0..count {
+        world.step(TICK, &mut ());
+    }
+}
+
+#[test]
+fn bodies_move_by_their_velocity() {
+    let mut world = World::new();
+    let slow = add_crate(&mut world, Vec2::new(0.0, 50.0), |body| body.with_velocity(Vec2::new(60.0, 0.0)));
+    let fast = add_crate(&mut world, Vec2::new(0.0, 100.0), |body| body.with_velocity(Vec2::new(0.0, 90.0)));
+    world.update_systems(&mut ());
+    world.set_velocity(&slow, Vec2::new(30.0, 0.0));
+
+    ticks(&mut world, 60);
+    // about a second, give or take the tick the bodies spawned in
+    assert!((world.get_pos(&slow).x - 30.0).abs() <= 1.0, "{}", world.get_pos(&slow));
+    assert!((world.get_pos(&fast).y - 190.0).abs() <= 2.0, "{}", world.get_pos(&fast));
+    assert_eq!(world.velocity(&slow), Vec2::new(30.0, 0.0));
+}
+
+#[test]
+fn gravity_scale_and_damping_change_velocity() {
+    let mut world = World::new();
+    world.set_gravity(Vec2::new(0.0, -600.0));
+    let falling = add_crate(&mut world, Vec2::new(0.0, 500.0), |body| body.with_velocity(Vec2::ZERO));
+    let floating = add_crate(&mut world, Vec2::new(20.0, 500.0), |body| body.with_gravity_scale(0.0));
+    let heavy = add_crate(&mut world, Vec2::new(40.0, 500.0), |body| body.with_gravity_scale(2.0));
+    let rocket = add_crate(&mut world, Vec2::new(60.0, 500.0), |body| body.with_gravity_scale(0.0).with_acceleration(Vec2::new(60.0, 0.0)));
+    let drifting = add_crate(&mut world, Vec2::new(80.0, 500.0), |body| body.with_velocity(Vec2::new(100.0, 0.0)).with_gravity_scale(0.0).with_damping(1.0));
+    world.update_systems(&mut ());
+
+    ticks(&mut world, 30);
+    let falling_speed = world.velocity(&falling).y;
+    assert!(falling_speed < -250.0, "{}", falling_speed);
+    assert!((world.velocity(&heavy).y - 2.0 * falling_speed).abs() < 1.0);
+    assert_eq!(world.velocity(&floating), Vec2::ZERO);
+    assert_eq!(world.get_pos(&floating), Vec2::new(20.0, 500.0));
+    assert!(world.get_pos(&falling).y < 450.0);
+    assert!((world.velocity(&rocket).x - falling_speed / -10.0).abs() < 0.1);
+
+    // slows down to 1 / e after about a second
+    ticks(&mut world, 30);
+    let drift = world.velocity(&drifting).x;
+    assert!((33.0..40.0).contains(&drift), "{}", drift);
+    assert_eq!(world.velocity(&drifting).y, 0.0);
+}
+
+#[test]
+fn blocked_axes_lose_their_velocity() {
+    let mut world = World::new();
+    world.set_gravity(Vec2::new(0.0, -600.0));
+    let floor = world.add_actor(Floor);
+    let sliding = add_crate(&mut world, Vec2::new(0.0, 20.0), |body| body.with_velocity(Vec2::new(30.0, 0.0)));
+    world.update_systems(&mut ());
+    // and a wall from x 100
+    world.set_chain(&floor, &[Vec2::new(100.0, 0.0), Vec2::new(100.0, 200.0)], false);
+
+    // lands, but keeps going right
+    ticks(&mut world, 60);
+    assert_eq!(world.get_pos(&sliding).y, 0.0);
+    assert_eq!(world.velocity(&sliding), Vec2::new(30.0, 0.0));
+    assert!(world.last_movement(&sliding).unwrap().touching_below);
+
+    // until the wall
+    ticks(&mut world, 240);
+    assert_eq!(world.get_pos(&sliding), Vec2::new(92.0, 0.0));
+    assert_eq!(world.velocity(&sliding), Vec2::ZERO);
+    let results = world.last_movement(&sliding).unwrap();
+    assert!(results.touching_below && results.touching_right);
+}
@@ -0,0 +1,167 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+mod common;
+
+// a wall or platform
+fn add<T: Actor<()>>(world: &mut World, solid: T, pos: Vec2, size: Vec2) -> ID<T> {
+    common::spawn(world, solid, move |id| PhysicsBody::new(pos, Collider::aabb(pos, size), id, PhysicsClass::Solid))
+}
+
+fn add_one_way(world: &mut World, platform: Platform, pos: Vec2, size: Vec2) -> ID<Platform> {
+    common::spawn(world, platform, move |id| PhysicsBody::new(pos, Collider::aabb(pos, size), id, PhysicsClass::Solid).with_one_way(Vec2::Y))
+}
+
+fn add_player(world: &mut World, player: Player, pos: Vec2) -> ID<Player> {
+    common::spawn(world, player, move |id| PhysicsBody::new(pos, Collider::aabb(pos, Vec2::splat(8.0)), id, PhysicsClass::Actor))
+}
+
+struct Wall;
+
+impl Actor<()> for Wall {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+struct Platform {
+    velocity: Vec2,
+}
+
+impl Actor<()> for Platform {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.move_solid(&self.velocity.clone(), world);
+    }
+}
+
+struct Player {
+    velocity: Vec2,
+    clinging: bool,
+    squished: u32,
+    // a platform this player moves from its own update, see `the_moving_actor_is_not_asked_to_cling`
+    drives: Option<ID<Platform>>,
+}
+
+impl Player {
+    fn new(velocity: Vec2) -> Self {
+        Self { velocity, clinging: false, squished: 0, drives: None }
+    }
+}
+
+impl Actor<()> for Player {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        if let Some(platform) = self.drives {
+            world.move_solid(platform, &Vec2::new(0.0, 2.0));
+        }
+        self.move_and_slide(&self.velocity.clone(), world);
+    }
+
+    fn on_squish(&mut self, _id: &ID<Self>, _solid: TypedID, _world: &mut World) {
+        self.squished += 1;
+    }
+
+    fn is_clinging(&self, _id: &ID<Self>, _world: &World) -> bool {
+        self.clinging
+    }
+}
+
+fn run(world: &mut World, updates: u32) {
+    for _ in 0..updates {
+        world.update_systems(&mut ());
+    }
+}
+
+#[test]
+fn riders_are_carried() {
+    for one_way in [false, true] {
+        let mut world = World::new();
+        let platform = Platform { velocity: Vec2::new(1.5, 0.0) };
+        let platform = match one_way {
+            true => add_one_way(&mut world, platform, Vec2::ZERO, Vec2::new(40.0, 8.0)),
+            false => add(&mut world, platform, Vec2::ZERO, Vec2::new(40.0, 8.0)),
+        };
+        let player = add_player(&mut world, Player::new(Vec2::new(0.0, -3.0)), Vec2::new(10.0, 8.0));
+
+        run(&mut world, 10);
+        assert_eq!(world.get_pos(&player), Vec2::new(25.0, 8.0));
+
+        // lifted up, even through a one-way platform
+        world.query_mut::<Platform>().for_each(|(_, platform)| platform.velocity = Vec2::new(0.0, 2.5));
+        run(&mut world, 10);
+        assert_eq!(world.get_pos(&player), Vec2::new(25.0, 33.0));
+
+        // without gravity, the rider still stays on a platform moving down
+        world.query_mut::<Player>().for_each(|(_, player)| player.velocity = Vec2::ZERO);
+        world.query_mut::<Platform>().for_each(|(_, platform)| platform.velocity = Vec2::new(-1.0, -2.0));
+        run(&mut world, 10);
+        assert_eq!(world.get_pos(&player), Vec2::new(15.0, 13.0));
+        assert_eq!(world.get_pos(&platform), Vec2::new(5.0, 5.0));
+        assert_eq!(world.get(&player).unwrap().squished, 0);
+    }
+}
+
+#[test]
+fn actors_in_the_way_are_pushed_and_squished() {
+    let mut world = World::new();
+    add(&mut world, Wall, Vec2::new(-100.0, -8.0), Vec2::new(300.0, 8.0));
+    add(&mut world, Wall, Vec2::new(60.0, 0.0), Vec2::new(8.0, 40.0));
+    add(&mut world, Platform { velocity: Vec2::new(2.0, 0.0) }, Vec2::ZERO, Vec2::new(20.0, 16.0));
+    let player = add_player(&mut world, Player::new(Vec2::new(0.0, -3.0)), Vec2::new(30.0, 0.0));
+
+    // pushed along until it hits the wall on the right
+    run(&mut world, 10);
+    assert_eq!(world.get_pos(&player), Vec2::new(40.0, 0.0));
+    assert_eq!(world.get(&player).unwrap().squished, 0);
+
+    run(&mut world, 10);
+    assert_eq!(world.get_pos(&player), Vec2::new(52.0, 0.0));
+    assert!(world.get(&player).unwrap().squished > 0);
+}
+
+#[test]
+fn one_way_platforms_never_push() {
+    let mut world = World::new();
+    add_one_way(&mut world, Platform { velocity: Vec2::new(2.0, 0.0) }, Vec2::ZERO, Vec2::new(20.0, 8.0));
+    let player = add_player(&mut world, Player::new(Vec2::ZERO), Vec2::new(24.0, 0.0));
+
+    run(&mut world, 10);
+    assert_eq!(world.get_pos(&player), Vec2::new(24.0, 0.0));
+    assert_eq!(world.get(&player).unwrap().squished, 0);
+}
+
+#[test]
+fn clinging_actors_are_carried() {
+    for clinging in [false, true] {
+        let mut world = World::new();
+        add(&mut world, Platform { velocity: Vec2::new(0.0, 2.0) }, Vec2::new(0.0, -10.0), Vec2::new(20.0, 40.0));
+        let player = add_player(&mut world, Player { clinging, ..Player::new(Vec2::ZERO) }, Vec2::new(20.0, 0.0));
+
+        run(&mut world, 5);
+        assert_eq!(world.get_pos(&player).y, if clinging { 10.0 } else { 0.0 });
+    }
+}
+
+#[test]
+fn the_moving_actor_is_not_asked_to_cling() {
+    let mut world = World::new();
+    let platform = add(&mut world, Platform { velocity: Vec2::ZERO }, Vec2::new(0.0, -10.0), Vec2::new(20.0, 40.0));
+    // both players are the same type, the driver is borrowed while the other one is asked
+    let driver = add_player(&mut world, Player { clinging: true, drives: Some(platform), ..Player::new(Vec2::ZERO) }, Vec2::new(-8.0, 0.0));
+    let climber = add_player(&mut world, Player { clinging: true, ..Player::new(Vec2::ZERO) }, Vec2::new(20.0, 0.0));
+
+    run(&mut world, 5);
+    assert_eq!(world.get_pos(&platform).y, 0.0);
+    assert_eq!(world.get_pos(&climber).y, 10.0);
+    assert_eq!(world.get_pos(&driver).y, 0.0);
+}
@@ -0,0 +1,128 @@
+use vaabbit::entity::MovementResults;
+use vaabbit::physics::{PhysicsBody, PhysicsClass, Tile, TileMapCollider};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+mod common;
+
+// can be jumped through from below, a box or the owner of a chain or tilemap
+struct Platform;
+
+impl Actor<()> for Platform {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id).with_one_way(Vec2::Y)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+// moved by the tests
+struct Jumper;
+
+impl Actor<()> for Jumper {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        let pos = Vec2::new(20.0, -20.0);
+        PhysicsBody::new(pos, Collider::aabb(pos, Vec2::splat(8.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+type Layout = fn(&mut World);
+
+// the same platform three ways, with its top at y 8 from x 0 to 64
+fn box_platform(world: &mut World) {
+    common::spawn(world, Platform, common::placed(Collider::aabb(Vec2::ZERO, Vec2::new(64.0, 8.0)), PhysicsClass::Solid));
+}
+
+fn chain_platform(world: &mut World) {
+    let platform = common::spawn(world, Platform, common::placed(None, PhysicsClass::Solid));
+    world.update_systems(&mut ());
+    world.set_chain(&platform, &[Vec2::new(0.0, 8.0), Vec2::new(64.0, 8.0)], false);
+}
+
+fn tile_platform(world: &mut World) {
+    let platform = common::spawn(world, Platform, common::placed(None, PhysicsClass::Solid));
+    world.update_systems(&mut ());
+    let mut map = TileMapCollider::new(Vec2::new(0.0, -8.0), Vec2::splat(16.0), 4, 1);
+    for x in 0..4 {
+        map.set(IVec2::new(x, 0), Some(Tile::one_way()));
+    }
+    world.set_tilemap(&platform, map);
+}
+
+const LAYOUTS: [(&str, Layout); 3] = [("box", box_platform), ("chain", chain_platform), ("tiles", tile_platform)];
+
+// moves the jumper once a frame, returning the last results
+fn moves(world: &mut World, jumper: ID<Jumper>, step: Vec2, count: usize) -> MovementResults {
+    let mut results = world.move_and_slide(jumper, &step);
+    for _ in 1..count {
+        world.update_systems(&mut ());
+        results = world.move_and_slide(jumper, &step);
+    }
+    world.update_systems(&mut ());
+    results
+}
+
+fn setup(layout: Layout) -> (World, ID<Jumper>) {
+    let mut world = World::new();
+    layout(&mut world);
+    let jumper = world.add_actor(Jumper);
+    world.update_systems(&mut ());
+    (world, jumper)
+}
+
+#[test]
+fn platforms_are_jumped_through_and_stood_on() {
+    for (name, layout) in LAYOUTS {
+        let (mut world, jumper) = setup(layout);
+
+        // up from below and through
+        let results = moves(&mut world, jumper, Vec2::new(0.0, 4.0), 10);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(20.0, 20.0), "{}", name);
+        assert!(!results.touching_above, "{}", name);
+
+        // and back down onto it
+        let results = moves(&mut world, jumper, Vec2::new(0.0, -2.0), 10);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(20.0, 8.0), "{}", name);
+        assert!(results.touching_below, "{}", name);
+        let results = moves(&mut world, jumper, Vec2::new(6.0, -2.0), 2);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(32.0, 8.0), "{}", name);
+        assert!(results.touching_below, "{}", name);
+    }
+}
+
+#[test]
+fn platforms_can_be_dropped_through() {
+    for (name, layout) in LAYOUTS {
+        let (mut world, jumper) = setup(layout);
+        moves(&mut world, jumper, Vec2::new(0.0, 4.0), 10);
+        moves(&mut world, jumper, Vec2::new(0.0, -2.0), 10);
+
+        world.drop_through(&jumper, 10);
+        let results = moves(&mut world, jumper, Vec2::new(0.0, -2.0), 10);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(20.0, -12.0), "{}", name);
+        assert!(!results.touching_below, "{}", name);
+
+        // only for a while, then it lands again
+        moves(&mut world, jumper, Vec2::new(0.0, 4.0), 10);
+        let results = moves(&mut world, jumper, Vec2::new(0.0, -2.0), 10);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(20.0, 8.0), "{}", name);
+        assert!(results.touching_below, "{}", name);
+    }
+}
+
+#[test]
+fn platforms_dont_block_from_the_side() {
+    for (name, layout) in LAYOUTS {
+        let (mut world, jumper) = setup(layout);
+        moves(&mut world, jumper, Vec2::new(0.0, 4.0), 5);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(20.0, 0.0), "{}", name);
+
+        // inside the platform
+        moves(&mut world, jumper, Vec2::new(-4.0, 0.0), 10);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(-20.0, 0.0), "{}", name);
+        moves(&mut world, jumper, Vec2::new(4.0, 0.0), 30);
+        assert_eq!(world.get_pos(&jumper), Vec2::new(100.0, 0.0), "{}", name);
+    }
+}
@@ -0,0 +1,59 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+// moved by the tests, a fraction of a pixel at a time
+struct Snail;
+
+impl Actor<()> for Snail {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, Collider::aabb(Vec2::ZERO, Vec2::splat(8.0)), id, PhysicsClass::Actor).with_pixel_perfect()
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+// in the way of a snail going right, from x 10
+struct Wall;
+
+impl Actor<()> for Wall {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(10.0, -8.0), Collider::aabb(Vec2::new(10.0, -8.0), Vec2::new(8.0, 24.0)), id, PhysicsClass::Solid)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+#[test]
+fn slow_speeds_add_up_to_whole_pixels() {
+    let mut world = World::new();
+    let snail = world.add_actor(Snail);
+    world.update_systems(&mut ());
+
+    for n in 1..=30 {
+        world.move_and_slide(snail, &Vec2::new(0.3, -0.1));
+        let pos = world.get_pos(&snail);
+        assert_eq!(pos, pos.round(), "not on a pixel after {} moves", n);
+        // never more than half a pixel behind or ahead
+        assert!((pos - Vec2::new(0.3, -0.1) * n as f32).abs().max_element() <= 0.5, "{} after {} moves", pos, n);
+    }
+    assert_eq!(world.get_pos(&snail), Vec2::new(9.0, -3.0));
+}
+
+#[test]
+fn blocked_moves_drop_the_fraction() {
+    let mut world = World::new();
+    world.add_actor(Wall);
+    let snail = world.add_actor(Snail);
+    world.update_systems(&mut ());
+    for _ in 0..20 {
+        world.move_and_slide(snail, &Vec2::new(0.5, 0.0));
+    }
+    assert_eq!(world.get_pos(&snail), Vec2::new(2.0, 0.0));
+
+    // turning around starts from a whole pixel, with nothing pushing back towards the wall
+    world.move_and_slide(snail, &Vec2::new(-0.3, 0.0));
+    assert_eq!(world.get_pos(&snail), Vec2::new(2.0, 0.0));
+    world.move_and_slide(snail, &Vec2::new(-0.3, 0.0));
+    assert_eq!(world.get_pos(&snail), Vec2::new(1.0, 0.0));
+}
@@ -0,0 +1,121 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass, Tile, TileMapCollider};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+mod common;
+
+// a body of any shape, or the owner of a chain or tilemap when it has no collider
+struct Thing;
+
+impl Actor<()> for Thing {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+fn thing(world: &mut World, pos: Vec2, collider: Option<Collider>, class: PhysicsClass) -> ID<Thing> {
+    common::spawn(world, Thing, move |id| PhysicsBody::new(pos, collider, id, class))
+}
+
+// a coin at x 20 in front of a wall at x 40
+fn corridor(world: &mut World) -> (ID<Thing>, ID<Thing>) {
+    let coin = thing(world, Vec2::new(20.0, 0.0), Collider::circle(Vec2::new(20.0, 0.0), 4.0), PhysicsClass::Zone);
+    let wall = thing(world, Vec2::new(40.0, -20.0), Collider::aabb(Vec2::new(40.0, -20.0), Vec2::new(8.0, 40.0)), PhysicsClass::Solid);
+    world.update_systems(&mut ());
+    (coin, wall)
+}
+
+fn solids(body: &PhysicsBody) -> bool {
+    body.class == PhysicsClass::Solid
+}
+
+#[test]
+fn rays_hit_the_nearest_body() {
+    let mut world = World::new();
+    let (coin, wall) = corridor(&mut world);
+
+    let hit = world.raycast(Vec2::ZERO, Vec2::X, 100.0, |_| true).unwrap();
+    assert_eq!(hit.id, coin.into_typed_id());
+    assert_eq!(hit.point, Vec2::new(16.0, 0.0));
+    assert_eq!(hit.normal, Vec2::NEG_X);
+    assert_eq!(hit.distance, 16.0);
+
+    // the filter skips the coin, and the direction doesn't need to be normalized
+    let hit = world.raycast(Vec2::ZERO, Vec2::new(3.0, 0.0), 100.0, solids).unwrap();
+    assert_eq!(hit.id, wall.into_typed_id());
+    assert_eq!(hit.distance, 40.0);
+
+    // from below the coin, hitting its underside
+    let hit = world.raycast(Vec2::new(20.0, -10.0), Vec2::Y, 100.0, |_| true).unwrap();
+    assert!(hit.point.abs_diff_eq(Vec2::new(20.0, -4.0), 0.001));
+    assert!(hit.normal.abs_diff_eq(Vec2::NEG_Y, 0.001));
+}
+
+#[test]
+fn rays_stop_at_their_length() {
+    let mut world = World::new();
+    corridor(&mut world);
+
+    assert!(world.raycast(Vec2::ZERO, Vec2::X, 15.0, |_| true).is_none());
+    assert!(world.raycast(Vec2::ZERO, Vec2::X, 39.0, solids).is_none());
+    assert!(world.raycast(Vec2::ZERO, Vec2::X, 40.0, solids).is_some());
+    // pointing away
+    assert!(world.raycast(Vec2::ZERO, Vec2::NEG_X, 100.0, |_| true).is_none());
+    assert!(world.raycast_all(Vec2::ZERO, Vec2::ZERO, 100.0, |_| true).is_empty());
+}
+
+#[test]
+fn rays_starting_inside_hit_at_once() {
+    let mut world = World::new();
+    let (coin, _) = corridor(&mut world);
+
+    let hit = world.raycast(Vec2::new(20.0, 0.0), Vec2::X, 100.0, |_| true).unwrap();
+    assert_eq!(hit.id, coin.into_typed_id());
+    assert_eq!(hit.distance, 0.0);
+    assert_eq!(hit.normal, Vec2::NEG_X);
+}
+
+#[test]
+fn all_hits_are_sorted_by_distance() {
+    let mut world = World::new();
+    let (coin, wall) = corridor(&mut world);
+    let near = thing(&mut world, Vec2::new(8.0, -2.0), Collider::aabb(Vec2::new(8.0, -2.0), Vec2::new(2.0, 4.0)), PhysicsClass::Solid);
+    world.update_systems(&mut ());
+
+    let hits = world.raycast_all(Vec2::ZERO, Vec2::X, 100.0, |_| true);
+    let ids: Vec<_> = hits.iter().map(|hit| hit.id).collect();
+    assert_eq!(ids, [near.into_typed_id(), coin.into_typed_id(), wall.into_typed_id()]);
+    assert_eq!(hits.iter().map(|hit| hit.distance).collect::<Vec<_>>(), [8.0, 16.0, 40.0]);
+}
+
+#[test]
+fn rays_hit_chains_and_tiles() {
+    // a floor at y 0, and a ray straight down onto it
+    let mut world = World::new();
+    let floor = thing(&mut world, Vec2::ZERO, None, PhysicsClass::Solid);
+    world.update_systems(&mut ());
+    world.set_chain(&floor, &[Vec2::new(-50.0, 0.0), Vec2::new(0.0, 0.0), Vec2::new(50.0, 0.0)], false);
+    let hit = world.raycast(Vec2::new(10.0, 20.0), Vec2::NEG_Y, 100.0, |_| true).unwrap();
+    assert_eq!(hit.id, floor.into_typed_id());
+    assert_eq!(hit.distance, 20.0);
+    assert!(hit.normal.abs_diff_eq(Vec2::Y, 0.001));
+    assert!(hit.tile.is_none());
+
+    // the third tile along, which tells which cell it was
+    let mut world = World::new();
+    let level = thing(&mut world, Vec2::ZERO, None, PhysicsClass::Solid);
+    world.update_systems(&mut ());
+    let mut map = TileMapCollider::new(Vec2::ZERO, Vec2::splat(16.0), 8, 1);
+    map.set(IVec2::new(2, 0), Some(Tile::solid()));
+    map.set(IVec2::new(5, 0), Some(Tile::solid()));
+    world.set_tilemap(&level, map);
+    let hit = world.raycast(Vec2::new(0.0, 8.0), Vec2::X, 100.0, |_| true).unwrap();
+    assert_eq!(hit.id, level.into_typed_id());
+    assert_eq!(hit.distance, 32.0);
+    assert_eq!(hit.normal, Vec2::NEG_X);
+    assert_eq!(hit.tile.unwrap().cell, IVec2::new(2, 0));
+    // only once, at the nearest tile
+    assert_eq!(world.raycast_all(Vec2::new(0.0, 8.0), Vec2::X, 100.0, |_| true).len(), 1);
+}
@@ -0,0 +1,82 @@
+use vaabbit::world::Phase;
+use vaabbit::*;
+
+// names of the types in the order they updated
+type Log = Vec<&'static str>;
+
+struct Camera;
+struct Enemy;
+struct Input;
+struct Player;
+
+impl Actor<Log> for Camera {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, log: &mut Log) {
+        log.push("camera");
+    }
+}
+
+impl Actor<Log> for Enemy {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, log: &mut Log) {
+        log.push("enemy");
+    }
+}
+
+impl Actor<Log> for Input {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, log: &mut Log) {
+        log.push("input");
+    }
+}
+
+impl Actor<Log> for Player {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, log: &mut Log) {
+        log.push("player");
+    }
+}
+
+fn update_order(world: &mut World) -> Log {
+    let mut log = Log::new();
+    world.update_systems(&mut log);
+    log
+}
+
+#[test]
+fn order_does_not_depend_on_spawn_order() {
+    let mut first = World::new();
+    first.add_actor(Player);
+    first.add_actor(Enemy);
+    let mut second = World::new();
+    second.add_actor(Enemy);
+    second.add_actor(Player);
+
+    assert_eq!(update_order(&mut first), update_order(&mut second));
+}
+
+#[test]
+fn types_run_in_their_phase_and_declared_order() {
+    let mut world = World::new();
+    world.configure::<Camera>().in_phase(Phase::PostUpdate);
+    world.configure::<Input>().in_phase(Phase::PreUpdate);
+    world.configure::<Player>().before::<Enemy>();
+    // spawned in the worst order
+    world.add_actor(Camera);
+    world.add_actor(Enemy);
+    world.add_actor(Player);
+    world.add_actor(Input);
+
+    assert_eq!(update_order(&mut world), ["input", "player", "enemy", "camera"]);
+
+    // reconfigured later on
+    world.configure::<Camera>().in_phase(Phase::PreUpdate).after::<Input>();
+    assert_eq!(update_order(&mut world), ["input", "camera", "player", "enemy"]);
+}
+
+#[test]
+#[should_panic(expected = "cycle")]
+fn cycles_are_reported() {
+    let mut world = World::new();
+    world.configure::<Player>().before::<Enemy>();
+    world.configure::<Enemy>().before::<Player>();
+    world.add_actor(Player);
+    world.add_actor(Enemy);
+    world.update_systems(&mut Log::new());
+}
@@ -0,0 +1,113 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass, Tile, TileMapCollider};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+mod common;
+
+// a solid of any shape, or the owner of a tilemap when it has no collider
+struct Block;
+
+impl Actor<()> for Block {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+fn block(world: &mut World, pos: Vec2, collider: Option<Collider>) -> ID<Block> {
+    common::spawn(world, Block, move |id| PhysicsBody::new(pos, collider, id, PhysicsClass::Solid))
+}
+
+// a wall from x 40 to 48
+fn wall(world: &mut World) -> ID<Block> {
+    let pos = Vec2::new(40.0, -20.0);
+    let wall = block(world, pos, Collider::aabb(pos, Vec2::new(8.0, 40.0)));
+    world.update_systems(&mut ());
+    wall
+}
+
+fn square() -> Collider {
+    Collider::aabb(Vec2::ZERO, Vec2::splat(8.0)).unwrap()
+}
+
+fn ball() -> Collider {
+    Collider::circle(Vec2::ZERO, 4.0).unwrap()
+}
+
+#[test]
+fn boxes_and_circles_stop_at_the_first_solid() {
+    let mut world = World::new();
+    let wall = wall(&mut world);
+
+    let hit = world.shape_cast(&square(), Vec2::new(0.0, -4.0), Vec2::new(100.0, 0.0), |_| true).unwrap();
+    assert_eq!(hit.id, wall.into_typed_id());
+    assert!((hit.toi - 0.32).abs() < 0.001, "{}", hit.toi);
+    assert!(hit.pos.abs_diff_eq(Vec2::new(32.0, -4.0), 0.01));
+    assert_eq!(hit.normal, Vec2::NEG_X);
+
+    // placed by its center
+    let hit = world.shape_cast(&ball(), Vec2::ZERO, Vec2::new(100.0, 0.0), |_| true).unwrap();
+    assert!((hit.toi - 0.36).abs() < 0.001, "{}", hit.toi);
+    assert!(hit.pos.abs_diff_eq(Vec2::new(36.0, 0.0), 0.01));
+    assert!(hit.normal.abs_diff_eq(Vec2::NEG_X, 0.001));
+}
+
+#[test]
+fn casts_only_go_as_far_as_delta() {
+    let mut world = World::new();
+    wall(&mut world);
+
+    assert!(world.shape_cast(&square(), Vec2::ZERO, Vec2::new(31.0, 0.0), |_| true).is_none());
+    assert!(world.shape_cast(&ball(), Vec2::ZERO, Vec2::new(0.0, 100.0), |_| true).is_none());
+    assert!(world.shape_cast(&ball(), Vec2::ZERO, Vec2::ZERO, |_| true).is_none());
+    assert!(world.shape_cast(&ball(), Vec2::ZERO, Vec2::new(100.0, 0.0), |_| false).is_none());
+}
+
+#[test]
+fn casts_starting_inside_hit_at_once() {
+    let mut world = World::new();
+    let wall = wall(&mut world);
+
+    for delta in [Vec2::new(10.0, 0.0), Vec2::ZERO] {
+        let hit = world.shape_cast(&square(), Vec2::new(36.0, 0.0), delta, |_| true).unwrap();
+        assert_eq!(hit.id, wall.into_typed_id());
+        assert_eq!(hit.toi, 0.0);
+        assert_eq!(hit.pos, Vec2::new(36.0, 0.0));
+    }
+}
+
+#[test]
+fn casts_hit_the_nearest_of_several() {
+    let mut world = World::new();
+    wall(&mut world);
+    let pos = Vec2::new(20.0, -20.0);
+    let post = block(&mut world, pos, Collider::aabb(pos, Vec2::new(2.0, 40.0)));
+    world.update_systems(&mut ());
+
+    let hit = world.shape_cast(&square(), Vec2::ZERO, Vec2::new(100.0, 0.0), |_| true).unwrap();
+    assert_eq!(hit.id, post.into_typed_id());
+    assert!((hit.toi - 0.12).abs() < 0.001, "{}", hit.toi);
+    // and diagonally, landing on top of it
+    let hit = world.shape_cast(&ball(), Vec2::new(21.0, 40.0), Vec2::new(0.0, -40.0), |_| true).unwrap();
+    assert_eq!(hit.id, post.into_typed_id());
+    assert!(hit.pos.abs_diff_eq(Vec2::new(21.0, 24.0), 0.01));
+    assert!(hit.normal.abs_diff_eq(Vec2::Y, 0.001));
+}
+
+#[test]
+fn casts_hit_tiles() {
+    let mut world = World::new();
+    let level = block(&mut world, Vec2::ZERO, None);
+    world.update_systems(&mut ());
+    let mut map = TileMapCollider::new(Vec2::ZERO, Vec2::splat(16.0), 8, 1);
+    map.set(IVec2::new(2, 0), Some(Tile::solid()));
+    map.set(IVec2::new(5, 0), Some(Tile::solid()));
+    world.set_tilemap(&level, map);
+
+    let hit = world.shape_cast(&square(), Vec2::new(0.0, 4.0), Vec2::new(64.0, 0.0), |_| true).unwrap();
+    assert_eq!(hit.id, level.into_typed_id());
+    assert!((hit.toi - 0.375).abs() < 0.001, "{}", hit.toi);
+    assert_eq!(hit.normal, Vec2::NEG_X);
+    assert_eq!(hit.tile.unwrap().cell, IVec2::new(2, 0));
+}
@@ -0,0 +1,114 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::{Capsule, Circle, Collider, Shape, AABB, OBB};
+use vaabbit::*;
+
+fn aabb(min: Vec2, max: Vec2) -> AABB {
+    AABB { min, max }
+}
+
+#[test]
+fn circle_overlaps_aabb() {
+    let square = aabb(Vec2::ZERO, Vec2::splat(10.0));
+
+    // inside, overlapping an edge, and near a corner on either side of the radius
+    assert!(Circle::new(Vec2::splat(5.0), 2.0).overlaps_aabb(&square));
+    assert!(Circle::new(Vec2::new(11.0, 5.0), 2.0).overlaps_aabb(&square));
+    assert!(Circle::new(Vec2::splat(11.0), 2.0).overlaps_aabb(&square));
+    assert!(!Circle::new(Vec2::splat(12.0), 2.0).overlaps_aabb(&square));
+    assert!(!Circle::new(Vec2::new(-3.0, 5.0), 2.0).overlaps_aabb(&square));
+    // a circle around the whole box
+    assert!(Circle::new(Vec2::splat(5.0), 50.0).overlaps_aabb(&square));
+}
+
+#[test]
+fn polygon_sat_tests_both_shapes_axes() {
+    let triangle = Collider::polygon(Vec2::ZERO, &[Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0)]).unwrap();
+    let square = Collider::polygon(Vec2::ZERO, &[Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0), Vec2::new(0.0, 10.0)]).unwrap();
+    let diamond = |center: Vec2| Collider::polygon(center, &[Vec2::new(0.0, -4.0), Vec2::new(4.0, 0.0), Vec2::new(0.0, 4.0), Vec2::new(-4.0, 0.0)]).unwrap();
+
+    // only the triangle's slanted edge separates it from the box
+    assert!(!triangle.overlaps(&Collider::aabb(Vec2::splat(6.0), Vec2::splat(2.0)).unwrap()));
+    assert!(triangle.overlaps(&Collider::aabb(Vec2::splat(4.0), Vec2::splat(2.0)).unwrap()));
+    assert!(!triangle.overlaps(&Collider::circle(Vec2::splat(7.5), 2.0).unwrap()));
+    assert!(triangle.overlaps(&Collider::circle(Vec2::splat(6.0), 2.0).unwrap()));
+
+    // only the diamond's edges separate it from the square
+    assert!(!square.overlaps(&diamond(Vec2::splat(13.0))));
+    assert!(!diamond(Vec2::splat(13.0)).overlaps(&square));
+    assert!(square.overlaps(&diamond(Vec2::splat(11.0))));
+    assert!(diamond(Vec2::splat(11.0)).overlaps(&square));
+}
+
+#[test]
+fn polygons_reject_bad_input() {
+    assert!(Collider::polygon(Vec2::ZERO, &[Vec2::ZERO, Vec2::X]).is_none());
+    // concave
+    assert!(Collider::polygon(Vec2::ZERO, &[Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(5.0, 2.0), Vec2::new(5.0, 10.0)]).is_none());
+    // clockwise winding is accepted
+    assert!(Collider::polygon(Vec2::ZERO, &[Vec2::ZERO, Vec2::new(0.0, 10.0), Vec2::new(10.0, 0.0)]).is_some());
+}
+
+#[test]
+fn capsule_and_obb_bounds_are_tight() {
+    let capsule = Capsule::new(Vec2::ZERO, 4.0, 2.0);
+    assert_eq!(capsule.bounds(), aabb(Vec2::new(-2.0, -6.0), Vec2::new(2.0, 6.0)));
+    let lying = capsule.with_rotation(std::f32::consts::FRAC_PI_2).bounds();
+    assert!(lying.min.abs_diff_eq(Vec2::new(-6.0, -2.0), 0.001) && lying.max.abs_diff_eq(Vec2::new(6.0, 2.0), 0.001));
+
+    assert_eq!(OBB::new(Vec2::new(5.0, 5.0), Vec2::new(8.0, 4.0), 0.0).bounds(), aabb(Vec2::new(1.0, 3.0), Vec2::new(9.0, 7.0)));
+    let diamond = OBB::new(Vec2::ZERO, Vec2::splat(4.0), std::f32::consts::FRAC_PI_4).bounds();
+    let corner = 2.0 * std::f32::consts::SQRT_2;
+    assert!(diamond.min.abs_diff_eq(Vec2::splat(-corner), 0.001) && diamond.max.abs_diff_eq(Vec2::splat(corner), 0.001));
+}
+
+#[test]
+fn capsules_and_obbs_overlap_by_their_shape() {
+    let capsule = Collider::capsule(Vec2::ZERO, 4.0, 2.0).unwrap();
+    let diamond = Collider::obb(Vec2::ZERO, Vec2::splat(4.0), std::f32::consts::FRAC_PI_4).unwrap();
+    let square = |min: Vec2| Collider::aabb(min, Vec2::splat(2.0)).unwrap();
+
+    // inside the bounds, but past the rounded cap
+    assert!(!capsule.overlaps(&square(Vec2::new(1.5, 5.5))));
+    assert!(capsule.overlaps(&square(Vec2::new(1.0, 4.5))));
+    assert!(capsule.overlaps(&square(Vec2::new(1.5, -1.0))));
+    // past the rotated edge
+    assert!(!diamond.overlaps(&square(Vec2::splat(1.6))));
+    assert!(diamond.overlaps(&square(Vec2::splat(1.0))));
+
+    // against every other collider, both ways around
+    let others = [
+        (Collider::circle(Vec2::new(0.0, 7.5), 2.0).unwrap(), true, false),
+        (Collider::circle(Vec2::new(3.0, 3.0), 1.0).unwrap(), false, false),
+        (Collider::polygon(Vec2::new(1.5, 0.0), &[Vec2::ZERO, Vec2::new(4.0, -2.0), Vec2::new(4.0, 2.0)]).unwrap(), true, true),
+        (Collider::capsule(Vec2::new(3.5, 0.0), 4.0, 2.0).unwrap(), true, true),
+        (Collider::obb(Vec2::new(0.0, 7.5), Vec2::new(2.0, 4.0), 0.3).unwrap(), true, false),
+    ];
+    for (other, hits_capsule, hits_diamond) in others {
+        assert_eq!(capsule.overlaps(&other), hits_capsule, "capsule against {:?}", other);
+        assert_eq!(other.overlaps(&capsule), hits_capsule, "{:?} against capsule", other);
+        assert_eq!(diamond.overlaps(&other), hits_diamond, "diamond against {:?}", other);
+        assert_eq!(other.overlaps(&diamond), hits_diamond, "{:?} against diamond", other);
+    }
+}
+
+struct Ramp;
+
+impl Actor<()> for Ramp {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        let triangle = Collider::polygon(Vec2::ZERO, &[Vec2::ZERO, Vec2::new(20.0, 0.0), Vec2::new(20.0, 10.0)]);
+        PhysicsBody::new(Vec2::ZERO, triangle, id, PhysicsClass::Solid).set_origin_as_center(true, true)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+#[test]
+fn polygon_bodies_follow_their_origin() {
+    let mut world = World::new();
+    let ramp = world.add_actor(Ramp);
+    world.set_pos(ramp, Vec2::new(100.0, 50.0));
+
+    let body = world.get_physics_body(&ramp).unwrap();
+    assert_eq!(body.origin(), Vec2::new(10.0, 5.0));
+    assert_eq!(body.bounds(), aabb(Vec2::new(90.0, 45.0), Vec2::new(110.0, 55.0)));
+}
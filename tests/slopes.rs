@@ -0,0 +1,176 @@
+use vaabbit::entity::MovementResults;
+use vaabbit::physics::{PhysicsBody, PhysicsClass, Tile, TileMapCollider};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+mod common;
+
+// a polygon or box, or the owner of a chain or tilemap when it has no collider
+struct Ground;
+
+impl Actor<()> for Ground {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+struct Walker {
+    step: Vec2,
+    // results of every move so far
+    moves: Vec<MovementResults>,
+}
+
+impl Actor<()> for Walker {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        common::next_body(id).with_max_slope(50f32.to_radians())
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        let step = self.step;
+        let results = self.move_and_slide(&step, world);
+        self.moves.push(results);
+    }
+}
+
+// the same ground three ways: a floor at y 16 up to x 32, a 1:2 ramp up to x 96, and
+// a plateau at y 48 from there
+const RAMP: [Vec2; 4] = [Vec2::new(-100.0, 16.0), Vec2::new(32.0, 16.0), Vec2::new(96.0, 48.0), Vec2::new(300.0, 48.0)];
+
+fn ground(world: &mut World, collider: Option<Collider>) -> ID<Ground> {
+    common::spawn(world, Ground, common::placed(collider, PhysicsClass::Solid))
+}
+
+fn add_walker(world: &mut World, pos: Vec2, step: Vec2) -> ID<Walker> {
+    let walker = Walker { step, moves: Vec::new() };
+    common::spawn(world, walker, move |id| PhysicsBody::new(pos, Collider::aabb(pos, Vec2::splat(8.0)), id, PhysicsClass::Actor))
+}
+
+fn polygon_ramp(world: &mut World) {
+    ground(world, Collider::aabb(Vec2::new(-100.0, 0.0), Vec2::new(132.0, 16.0)));
+    ground(world, Collider::polygon(Vec2::new(32.0, 16.0), &[Vec2::ZERO, Vec2::new(64.0, 0.0), Vec2::new(64.0, 32.0)]));
+    ground(world, Collider::aabb(Vec2::new(32.0, 0.0), Vec2::new(64.0, 16.0)));
+    ground(world, Collider::aabb(Vec2::new(96.0, 0.0), Vec2::new(204.0, 48.0)));
+}
+
+fn chain_ramp(world: &mut World) {
+    let terrain = ground(world, None);
+    world.set_chain(&terrain, &RAMP, false);
+}
+
+fn tile_ramp(world: &mut World) {
+    let terrain = ground(world, None);
+    let mut map = TileMapCollider::new(Vec2::ZERO, Vec2::splat(16.0), 20, 4);
+    for x in 0..20 {
+        map.set(IVec2::new(x, 0), Some(Tile::solid()));
+    }
+    // half a tile up per tile, two tiles in each row, on top of solid ones
+    for (x, y) in [(2, 1), (4, 2)] {
+        map.set(IVec2::new(x, y), Some(Tile::slope(0.0, 0.5)));
+        map.set(IVec2::new(x + 1, y), Some(Tile::slope(0.5, 1.0)));
+        for x in x + 2..20 {
+            map.set(IVec2::new(x, y), Some(Tile::solid()));
+        }
+    }
+    world.set_tilemap(&terrain, map);
+}
+
+// builds the ground in a new world
+type Layout = fn(&mut World);
+
+const RAMPS: [(&str, Layout); 3] = [("polygon", polygon_ramp), ("chain", chain_ramp), ("tiles", tile_ramp)];
+
+fn walk(build: Layout, start: Vec2, step: Vec2, frames: usize) -> (Vec2, Vec<MovementResults>) {
+    let mut world = World::new();
+    build(&mut world);
+    let walker = add_walker(&mut world, start, step);
+    for _ in 0..frames {
+        world.update_systems(&mut ());
+    }
+    let moves = world.get(&walker).unwrap().moves.clone();
+    (world.get_pos(&walker), moves)
+}
+
+#[test]
+fn walkers_climb_ramps() {
+    let slope = Vec2::new(-1.0, 2.0).normalize();
+    for (name, build) in RAMPS {
+        let (pos, moves) = walk(build, Vec2::new(8.0, 16.0), Vec2::new(2.0, -3.0), 60);
+
+        // keeps its horizontal speed all the way up
+        assert_eq!(pos, Vec2::new(128.0, 48.0), "{}", name);
+        assert!(moves.iter().all(|results| results.touching_below), "{}", name);
+        // halfway up, standing on the ramp
+        let normal = moves[30].ground_normal.unwrap();
+        assert!(normal.abs_diff_eq(slope, 0.001), "{}: {}", name, normal);
+        let last = moves.last().unwrap();
+        assert!(!last.touching_right, "{}", name);
+        assert_eq!(last.ground_normal, Some(Vec2::Y), "{}", name);
+    }
+}
+
+#[test]
+fn walkers_stay_on_ramps_going_down() {
+    for (name, build) in RAMPS {
+        // faster down the ramp than falling, so without snapping it would leave the ground
+        let (pos, moves) = walk(build, Vec2::new(128.0, 48.0), Vec2::new(-4.0, -1.0), 30);
+
+        assert_eq!(pos, Vec2::new(8.0, 16.0), "{}", name);
+        assert!(moves.iter().all(|results| results.touching_below), "{}", name);
+    }
+}
+
+#[test]
+fn walkers_fall_off_ledges() {
+    // walking off the plateau to the left, there is a drop of 32 pixels
+    let mut world = World::new();
+    ground(&mut world, Collider::aabb(Vec2::new(-100.0, 0.0), Vec2::new(200.0, 16.0)));
+    ground(&mut world, Collider::aabb(Vec2::new(40.0, 16.0), Vec2::new(60.0, 32.0)));
+    let walker = add_walker(&mut world, Vec2::new(48.0, 48.0), Vec2::new(-4.0, -1.0));
+    for _ in 0..5 {
+        world.update_systems(&mut ());
+    }
+
+    // past the edge, falling instead of snapping down to the floor
+    assert_eq!(world.get_pos(&walker), Vec2::new(28.0, 47.0));
+    assert!(!world.get(&walker).unwrap().moves.last().unwrap().touching_below);
+}
+
+// a floor at y 16 up to x 32, rising 2:1 up to x 40 and flat at y 32 from there
+fn polygon_wall(world: &mut World) {
+    ground(world, Collider::aabb(Vec2::new(-100.0, 0.0), Vec2::new(400.0, 16.0)));
+    ground(world, Collider::polygon(Vec2::new(32.0, 16.0), &[Vec2::ZERO, Vec2::new(8.0, 0.0), Vec2::new(8.0, 16.0)]));
+    ground(world, Collider::aabb(Vec2::new(40.0, 16.0), Vec2::new(100.0, 16.0)));
+}
+
+fn chain_wall(world: &mut World) {
+    let terrain = ground(world, None);
+    world.set_chain(&terrain, &[Vec2::new(-100.0, 16.0), Vec2::new(32.0, 16.0), Vec2::new(40.0, 32.0), Vec2::new(200.0, 32.0)], false);
+}
+
+fn tile_wall(world: &mut World) {
+    let terrain = ground(world, None);
+    // narrow tiles, so a slope across one is steeper than 45 degrees
+    let mut map = TileMapCollider::new(Vec2::ZERO, Vec2::new(8.0, 16.0), 20, 2);
+    for x in 0..20 {
+        map.set(IVec2::new(x, 0), Some(Tile::solid()));
+    }
+    map.set(IVec2::new(4, 1), Some(Tile::slope(0.0, 1.0)));
+    for x in 5..20 {
+        map.set(IVec2::new(x, 1), Some(Tile::solid()));
+    }
+    world.set_tilemap(&terrain, map);
+}
+
+#[test]
+fn walkers_stop_at_slopes_steeper_than_max_slope() {
+    for (name, build) in [("polygon", polygon_wall as Layout), ("chain", chain_wall), ("tiles", tile_wall)] {
+        let (pos, moves) = walk(build, Vec2::new(8.0, 16.0), Vec2::new(2.0, -3.0), 20);
+
+        assert_eq!(pos, Vec2::new(24.0, 16.0), "{}", name);
+        let last = moves.last().unwrap();
+        assert!(last.touching_below && last.touching_right, "{}", name);
+        assert_eq!(last.ground_normal, Some(Vec2::Y), "{}", name);
+    }
+}
@@ -0,0 +1,74 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use vaabbit::events::SubscriptionHandle;
+use vaabbit::*;
+
+struct Collected(u32);
+
+// emits `Collected` every update
+struct Coin;
+
+impl Actor<()> for Coin {
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.emit(world, Collected(5));
+    }
+}
+
+struct Hud;
+
+impl Actor<()> for Hud {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+// a coin and a hud counting its coins, and the count
+fn setup(world: &mut World) -> (ID<Coin>, ID<Hud>, Rc<Cell<u32>>, SubscriptionHandle) {
+    let coin = world.add_actor(Coin);
+    let hud = world.add_actor(Hud);
+    let total = Rc::new(Cell::new(0));
+    let counted = total.clone();
+    let handle = world.subscribe(coin, hud, move |_world, collected: &Collected| counted.set(counted.get() + collected.0));
+    (coin, hud, total, handle)
+}
+
+#[test]
+fn subscriptions_end_when_unsubscribed() {
+    let mut world = World::new();
+    let (_, _, total, handle) = setup(&mut world);
+
+    world.update_systems(&mut ());
+    world.update_systems(&mut ());
+    assert_eq!(total.get(), 10);
+
+    assert!(world.unsubscribe(handle));
+    assert!(!world.unsubscribe(handle));
+    world.update_systems(&mut ());
+    assert_eq!(total.get(), 10);
+    // the closure is gone with its captures
+    assert_eq!(Rc::strong_count(&total), 1);
+}
+
+#[test]
+fn subscriptions_end_with_their_listener() {
+    let mut world = World::new();
+    let (_, hud, total, _) = setup(&mut world);
+    world.update_systems(&mut ());
+
+    world.remove_actor(&hud);
+    world.update_systems(&mut ());
+    world.update_systems(&mut ());
+    assert_eq!(total.get(), 5);
+    assert_eq!(Rc::strong_count(&total), 1);
+}
+
+#[test]
+fn subscriptions_end_with_their_emitter() {
+    let mut world = World::new();
+    let (coin, _, total, handle) = setup(&mut world);
+    world.update_systems(&mut ());
+
+    world.remove_actor(&coin);
+    world.update_systems(&mut ());
+    assert_eq!(Rc::strong_count(&total), 1);
+    assert!(!world.unsubscribe(handle));
+}
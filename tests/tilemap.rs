@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use vaabbit::physics::{PhysicsBody, PhysicsClass, Tile, TileHit, TileMapCollider, ALL_LAYERS};
+use vaabbit::shapes::{Collider, AABB};
+use vaabbit::*;
+
+const SPIKES: u16 = 99;
+
+struct Level;
+
+impl Actor<()> for Level {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, None, id, PhysicsClass::Solid)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+struct Ball {
+    tile_below: Option<TileHit>,
+    tiles_hit: Rc<RefCell<Vec<TileHit>>>,
+}
+
+impl Actor<()> for Ball {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::new(4.0, 60.0), Collider::aabb(Vec2::new(4.0, 60.0), Vec2::splat(8.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.tile_below = self.move_and_slide(&Vec2::new(0.0, -3.0), world).tile_below;
+    }
+
+    fn on_tile_collision(&mut self, _id: &ID<Self>, tilemap: TypedID, tile: TileHit, _world: &mut World) {
+        assert!(tilemap.is::<Level>().is_some());
+        self.tiles_hit.borrow_mut().push(tile);
+    }
+}
+
+fn level(world: &mut World, tiles: &[(IVec2, Tile)]) -> ID<Level> {
+    let level = world.add_actor(Level);
+    let mut map = TileMapCollider::new(Vec2::ZERO, Vec2::splat(16.0), 8, 8);
+    for (cell, tile) in tiles {
+        map.set(*cell, Some(*tile));
+    }
+    world.set_tilemap(&level, map);
+    level
+}
+
+fn ball(world: &mut World) -> (ID<Ball>, Rc<RefCell<Vec<TileHit>>>) {
+    let tiles_hit = Rc::new(RefCell::new(Vec::new()));
+    (world.add_actor(Ball { tile_below: None, tiles_hit: tiles_hit.clone() }), tiles_hit)
+}
+
+#[test]
+fn actors_are_told_which_tiles_they_hit() {
+    let mut world = World::new();
+    let floor = Tile::solid().with_id(7);
+    let level = level(&mut world, &[(IVec2::new(0, 0), floor), (IVec2::new(1, 0), floor), (IVec2::new(0, 2), Tile::zone().with_id(SPIKES))]);
+    let (ball, tiles_hit) = ball(&mut world);
+
+    for _ in 0..30 {
+        world.update_systems(&mut ());
+    }
+    assert_eq!(world.get_pos(&ball), Vec2::new(4.0, 16.0));
+    assert_eq!(world.get(&ball).unwrap().tile_below, Some(TileHit { cell: IVec2::new(0, 0), tile: floor }));
+    // only the zone tile it fell through, solid tiles are never overlapped
+    assert!(!tiles_hit.borrow().is_empty());
+    assert!(tiles_hit.borrow().iter().all(|hit| hit.cell == IVec2::new(0, 2) && hit.tile.id == SPIKES));
+
+    world.tilemap_mut(&level).unwrap().set(IVec2::new(0, 0), None);
+    for _ in 0..10 {
+        world.update_systems(&mut ());
+    }
+    assert!(world.get_pos(&ball).y < 16.0);
+}
+
+#[test]
+fn flat_slopes_have_no_collider() {
+    let map = TileMapCollider::new(Vec2::ZERO, Vec2::splat(16.0), 4, 4);
+    assert!(map.tile_collider(IVec2::ZERO, &Tile::slope(0.0, 0.0)).is_none());
+    assert!(map.tile_collider(IVec2::ZERO, &Tile::slope(-1.0, 0.0)).is_none());
+    assert!(matches!(map.tile_collider(IVec2::ZERO, &Tile::slope(0.0, 0.5)), Some(Collider::Polygon(_))));
+    assert!(matches!(map.tile_collider(IVec2::ZERO, &Tile::solid()), Some(Collider::AABB(_))));
+
+    // nothing to land on or to hit
+    let mut world = World::new();
+    level(&mut world, &[(IVec2::new(0, 0), Tile::slope(0.0, 0.0)), (IVec2::new(0, 1), Tile::slope(0.0, 0.0))]);
+    let (ball, _) = ball(&mut world);
+    for _ in 0..30 {
+        world.update_systems(&mut ());
+    }
+    assert!(world.get_pos(&ball).y < 0.0);
+    assert!(world.raycast(Vec2::new(8.0, 40.0), Vec2::NEG_Y, 40.0, |_| true).is_none());
+}
+
+#[test]
+fn tiles_follow_their_actors_layers() {
+    let mut world = World::new();
+    let level = level(&mut world, &[(IVec2::new(0, 0), Tile::solid())]);
+    let (ball, _) = ball(&mut world);
+    let area = AABB::new(Vec2::new(4.0, 4.0), Vec2::new(12.0, 12.0));
+
+    // on no layer, so nothing collides with it anymore
+    world.set_collision_layers(&level, 0, ALL_LAYERS);
+    assert!(world.query_area(&area, ALL_LAYERS).is_empty());
+    for _ in 0..30 {
+        world.update_systems(&mut ());
+    }
+    assert!(world.get_pos(&ball).y < 0.0);
+
+    world.set_collision_layers(&level, 1 << 4, ALL_LAYERS);
+    assert_eq!(world.query_area(&area, 1 << 4).len(), 1);
+    assert!(world.query_area(&area, ALL_LAYERS & !(1 << 4)).is_empty());
+    // and the tiles hit by rays are on it too
+    let hit = world.raycast(Vec2::new(8.0, 40.0), Vec2::NEG_Y, 40.0, |body| body.layer == 1 << 4);
+    assert_eq!(hit.map(|hit| hit.distance), Some(24.0));
+}
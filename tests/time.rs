@@ -0,0 +1,105 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass, ALL_LAYERS};
+use vaabbit::shapes::{Collider, AABB};
+use vaabbit::*;
+
+const DT: f32 = 1.0 / 60.0;
+
+// records what `world.time()` said during its last update
+#[derive(Default)]
+struct Clock {
+    updates: u32,
+    frame: u64,
+    fixed_delta: f32,
+    scale: f32,
+}
+
+impl Clock {
+    fn record(&mut self, world: &World) {
+        self.updates += 1;
+        self.frame = world.time().frame();
+        self.fixed_delta = world.time().fixed_delta();
+        self.scale = world.time().scale();
+    }
+}
+
+impl Actor<()> for Clock {
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.record(world);
+    }
+}
+
+// the same, for a type with its own time scale
+#[derive(Default)]
+struct Enemy(Clock);
+
+impl Actor<()> for Enemy {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, Collider::aabb(Vec2::ZERO, Vec2::splat(8.0)), id, PhysicsClass::Solid)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.0.record(world);
+    }
+}
+
+#[test]
+fn time_counts_frames_and_elapsed_time() {
+    let mut world = World::new();
+    let clock = world.add_actor(Clock::default());
+
+    for _ in 0..30 {
+        world.step(DT, &mut ());
+    }
+
+    let clock = world.get(&clock).unwrap();
+    assert_eq!(clock.updates, 30);
+    // counted from 0 in the first update
+    assert_eq!(clock.frame, 29);
+    assert_eq!(world.time().frame(), 30);
+    assert_eq!(clock.fixed_delta, DT);
+    assert!((world.time().elapsed() - 0.5).abs() < 0.001);
+}
+
+#[test]
+fn the_time_scale_slows_down_everything() {
+    let mut world = World::new();
+    let clock = world.add_actor(Clock::default());
+    world.set_time_scale(0.5);
+
+    for _ in 0..60 {
+        world.step(DT, &mut ());
+    }
+
+    // still a tick every frame, each simulating half the time
+    let clock = world.get(&clock).unwrap();
+    assert_eq!(clock.updates, 60);
+    assert_eq!(clock.scale, 0.5);
+    assert_eq!(clock.fixed_delta, DT * 0.5);
+    assert!((world.time().elapsed() - 0.5).abs() < 0.001);
+}
+
+#[test]
+fn types_are_scaled_and_paused_on_their_own() {
+    let mut world = World::new();
+    let clock = world.add_actor(Clock::default());
+    let enemy = world.add_actor(Enemy::default());
+
+    world.set_time_scale_of::<Enemy>(0.25);
+    world.step(DT, &mut ());
+    assert_eq!(world.get(&enemy).unwrap().0.fixed_delta, DT * 0.25);
+    assert_eq!(world.get(&clock).unwrap().fixed_delta, DT);
+
+    world.set_time_scale_of::<Enemy>(0.0);
+    for _ in 0..10 {
+        world.step(DT, &mut ());
+    }
+    assert_eq!(world.get(&enemy).unwrap().0.updates, 1);
+    assert_eq!(world.get(&clock).unwrap().updates, 11);
+    // paused, but still there to collide with
+    let area = AABB::new(Vec2::new(-4.0, -4.0), Vec2::new(4.0, 4.0));
+    assert!(world.query_area(&area, ALL_LAYERS).contains(&enemy.into_typed_id()));
+
+    world.set_time_scale_of::<Enemy>(1.0);
+    world.step(DT, &mut ());
+    assert_eq!(world.get(&enemy).unwrap().0.updates, 2);
+}
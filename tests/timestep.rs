@@ -0,0 +1,84 @@
+use vaabbit::physics::{PhysicsBody, PhysicsClass};
+use vaabbit::shapes::Collider;
+use vaabbit::*;
+
+// moves 60 pixels per second of simulated time
+struct Runner {
+    ticks: u32,
+}
+
+impl Actor<()> for Runner {
+    fn init_physicsbody(id: TypedID) -> PhysicsBody {
+        PhysicsBody::new(Vec2::ZERO, Collider::aabb(Vec2::ZERO, Vec2::splat(8.0)), id, PhysicsClass::Actor)
+    }
+
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        self.ticks += 1;
+        let step = world.time().fixed_delta() * 60.0;
+        self.move_by(&Vec2::new(step, 0.0), world);
+    }
+}
+
+// ticks and position after `seconds` of frames at `hz`
+fn run_at(hz: u32, seconds: u32) -> (u32, Vec2) {
+    let mut world = World::new();
+    let runner = world.add_actor(Runner { ticks: 0 });
+    let mut ticks = 0;
+    for _ in 0..hz * seconds {
+        ticks += world.step(1.0 / hz as f32, &mut ());
+    }
+    assert_eq!(world.get(&runner).unwrap().ticks, ticks);
+    (ticks, world.get_pos(&runner))
+}
+
+#[test]
+fn the_simulation_runs_the_same_at_any_refresh_rate() {
+    let (ticks, pos) = run_at(60, 2);
+    assert_eq!(ticks, 120);
+    assert!((pos.x - 120.0).abs() < 0.01, "{}", pos);
+
+    for hz in [30, 144] {
+        // float error in the accumulator can leave the last tick for the next frame
+        let (other_ticks, other_pos) = run_at(hz, 2);
+        assert!(other_ticks.abs_diff(ticks) <= 1, "{} ticks at {} Hz", other_ticks, hz);
+        assert!((other_pos.x - other_ticks as f32).abs() < 0.01, "{} at {} Hz", other_pos, hz);
+    }
+}
+
+#[test]
+fn long_frames_run_a_limited_number_of_ticks() {
+    let mut world = World::new();
+    world.add_actor(Runner { ticks: 0 });
+
+    assert_eq!(world.step(1.0 / 120.0, &mut ()), 0);
+    assert_eq!(world.step(1.0 / 120.0, &mut ()), 1);
+
+    // a stall of ten seconds only catches up a quarter of one, about 15 ticks
+    let mut quarter = World::new();
+    quarter.add_actor(Runner { ticks: 0 });
+    let ticks = quarter.step(0.25, &mut ());
+    assert!((14..=15).contains(&ticks));
+    assert_eq!(world.step(10.0, &mut ()), ticks);
+}
+
+#[test]
+fn positions_are_interpolated_between_ticks() {
+    let mut world = World::new();
+    let runner = world.add_actor(Runner { ticks: 0 });
+
+    world.step(1.0 / 60.0, &mut ());
+    world.step(1.0 / 60.0, &mut ());
+    assert_eq!(world.get_pos(&runner), Vec2::new(2.0, 0.0));
+
+    // half way to the next tick
+    world.step(1.0 / 120.0, &mut ());
+    assert!((world.alpha() - 0.5).abs() < 0.001);
+    assert_eq!(world.interpolated_pos(&runner, 0.0), Some(Vec2::new(1.0, 0.0)));
+    assert_eq!(world.interpolated_pos(&runner, 1.0), Some(Vec2::new(2.0, 0.0)));
+    assert!(world.interpolated_pos(&runner, world.alpha()).unwrap().abs_diff_eq(Vec2::new(1.5, 0.0), 0.001));
+
+    // removed actors are nowhere, not at the origin
+    world.remove_actor(&runner);
+    world.step(1.0 / 60.0, &mut ());
+    assert_eq!(world.interpolated_pos(&runner, world.alpha()), None);
+}
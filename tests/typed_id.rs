@@ -0,0 +1,44 @@
+use vaabbit::*;
+
+struct Coin {
+    value: u32,
+}
+
+impl Actor<()> for Coin {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+struct Gem;
+
+impl Actor<()> for Gem {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {}
+}
+
+#[test]
+fn lookup_and_removal_through_typed_ids() {
+    let mut world = World::new();
+    let coin: TypedID = world.add_actor(Coin { value: 5 }).into();
+    let gem: TypedID = world.add_actor(Gem).into();
+    world.update_systems(&mut ());
+
+    assert!(world.is_alive(coin) && world.is_alive(gem));
+    assert_eq!(world.get_dyn(coin).and_then(|actor| actor.downcast_ref::<Coin>()).map(|coin| coin.value), Some(5));
+    assert!(world.get_dyn(gem).and_then(|actor| actor.downcast_ref::<Coin>()).is_none());
+    assert_eq!(world.type_name(coin), Some(std::any::type_name::<Coin>()));
+
+    world.remove_dyn(coin);
+    world.remove_dyn(coin);
+    world.update_systems(&mut ());
+    assert!(!world.is_alive(coin));
+    assert!(world.is_alive(gem));
+}
+
+#[test]
+fn type_names_are_per_world() {
+    let mut world = World::new();
+    let gem: TypedID = world.add_actor(Gem).into();
+
+    assert_eq!(world.type_name(gem), Some(std::any::type_name::<Gem>()));
+    assert_eq!(World::new().type_name(gem), None);
+    assert!(format!("{:?}", gem).starts_with("TypedID<"));
+}
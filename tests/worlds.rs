@@ -0,0 +1,84 @@
+use std::rc::Rc;
+
+use vaabbit::*;
+
+struct Counter {
+    updates: u32,
+    // dropped along with the actor, see `dropping_a_world_drops_its_actors`
+    _token: Rc<()>,
+}
+
+impl Actor<()> for Counter {
+    fn update(&mut self, _id: &ID<Self>, _world: &mut World, _ctx: &mut ()) {
+        self.updates += 1;
+    }
+}
+
+// spawns a copy of itself from `update`, while it is borrowed by the system
+struct Breeder;
+
+impl Actor<()> for Breeder {
+    fn update(&mut self, _id: &ID<Self>, world: &mut World, _ctx: &mut ()) {
+        world.add_actor(Breeder);
+    }
+}
+
+fn counter(token: &Rc<()>) -> Counter {
+    Counter { updates: 0, _token: token.clone() }
+}
+
+#[test]
+fn worlds_only_see_their_own_actors() {
+    let token = Rc::new(());
+    let mut menu = World::new();
+    let mut game = World::new();
+
+    let menu_id = menu.add_actor(counter(&token));
+    for _ in 0..3 {
+        game.add_actor(counter(&token));
+    }
+    menu.update_systems(&mut ());
+    game.update_systems(&mut ());
+    game.update_systems(&mut ());
+
+    assert_eq!(menu.query::<Counter>().count(), 1);
+    assert_eq!(game.query::<Counter>().count(), 3);
+    assert_eq!(menu.get(&menu_id).unwrap().updates, 1);
+    assert!(game.query::<Counter>().all(|(_, counter)| counter.updates == 2));
+}
+
+#[test]
+fn dropping_a_world_drops_its_actors() {
+    let token = Rc::new(());
+    let mut kept = World::new();
+    kept.add_actor(counter(&token));
+    kept.update_systems(&mut ());
+
+    {
+        let mut dropped = World::new();
+        for _ in 0..10 {
+            dropped.add_actor(counter(&token));
+        }
+        dropped.update_systems(&mut ());
+        assert_eq!(Rc::strong_count(&token), 12);
+    }
+
+    assert_eq!(Rc::strong_count(&token), 2);
+    kept.update_systems(&mut ());
+    assert_eq!(kept.query::<Counter>().next().unwrap().1.updates, 2);
+}
+
+#[test]
+fn spawning_the_same_type_during_update() {
+    let mut world = World::new();
+    world.add_actor(Breeder);
+
+    // doubles every update, growing the storage past its initial capacity
+    for _ in 0..12 {
+        world.update_systems(&mut ());
+    }
+    assert_eq!(world.query::<Breeder>().count(), 1 << 12);
+
+    let other = World::new();
+    assert_eq!(other.query::<Breeder>().count(), 0);
+}